@@ -0,0 +1,91 @@
+#![allow(clippy::needless_return)]
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use nbodysim_rust::{ChunkSizing, Vec2, VerletWorld};
+
+const COUNTS: [usize; 3] = [256, 1024, 4096];
+// the main binary's starting size, fixed so runs only differ by the code under test
+const CHUNK_SIZE: i32 = 20;
+
+// seeded cloud at rest with its grid built, identical on every call
+fn world(count: usize) -> VerletWorld {
+    let mut rnd = StdRng::seed_from_u64(37);
+    let mut world = VerletWorld::new(0, 540.0);
+    world.chunk_sizing = ChunkSizing::Fixed(CHUNK_SIZE);
+    world.chunk_size = CHUNK_SIZE;
+
+    for _step in 0..count {
+        let position = Vec2::new(rnd.gen_range(-540.0..540.0), rnd.gen_range(-540.0..540.0));
+        world.spawn(position, Vec2::ZERO, rnd.gen_range(1.0..50.0), rnd.gen_range(0.1..2.0));
+    }
+    world.build_chunks();
+
+    return world;
+}
+
+fn grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid_build");
+    for count in COUNTS {
+        let mut world = world(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(BenchmarkId::from_parameter(count), |b| b.iter(|| {
+            world.build_chunks();
+        }));
+    }
+    group.finish();
+}
+
+fn collisions(c: &mut Criterion) {
+    // collisions move bodies, so every iteration starts from a fresh world
+    let mut group = c.benchmark_group("collisions");
+    for count in COUNTS {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(BenchmarkId::new("grid", count), |b| b.iter_batched_ref(
+            || world(count),
+            |world| { world.resolve_collisions(); },
+            BatchSize::LargeInput,
+        ));
+        group.bench_function(BenchmarkId::new("bruteforce", count), |b| b.iter_batched_ref(
+            || world(count),
+            |world| { world.resolve_collisions_bruteforce(); },
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+fn gravity(c: &mut Criterion) {
+    // only accumulates acceleration, repeating it on one world is fine
+    let mut group = c.benchmark_group("gravity");
+    for count in COUNTS {
+        let mut world = world(count);
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(BenchmarkId::new("chunked", count), |b| b.iter(|| {
+            world.resolve_gravity();
+        }));
+        group.bench_function(BenchmarkId::new("bruteforce", count), |b| b.iter(|| {
+            world.resolve_gravity_bruteforce();
+        }));
+    }
+    group.finish();
+}
+
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    group.sample_size(20);
+    for count in COUNTS {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(BenchmarkId::from_parameter(count), |b| b.iter_batched_ref(
+            || world(count),
+            |world| { world.update(); },
+            BatchSize::LargeInput,
+        ));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, grid, collisions, gravity, update);
+criterion_main!(benches);
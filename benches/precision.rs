@@ -0,0 +1,52 @@
+#![allow(clippy::needless_return)]
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use nbodysim_rust::{Float, Vec2, VerletWorld};
+
+const COUNTS: [usize; 3] = [256, 1024, 4096];
+
+// same seeded cloud for both precisions
+fn world<F: Float>(count: usize) -> VerletWorld<F> {
+    let mut rnd = StdRng::seed_from_u64(37);
+    let mut world = VerletWorld::new(0, F::from_f64(540.0));
+
+    for _step in 0..count {
+        let position = Vec2::new(rnd.gen_range(-540.0..540.0), rnd.gen_range(-540.0..540.0));
+        world.spawn(position.cast(), Vec2::ZERO, F::from_f64(rnd.gen_range(1.0..50.0)), F::from_f64(rnd.gen_range(0.1..2.0)));
+    }
+
+    return world;
+}
+
+fn bench_precision<F: Float>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group(format!("gravity_bruteforce/{}", name));
+    for count in COUNTS {
+        let mut world = world::<F>(count);
+        group.throughput(Throughput::Elements((count * (count - 1) / 2) as u64));
+        group.bench_function(BenchmarkId::from_parameter(count), |b| b.iter(|| {
+            world.resolve_gravity_bruteforce();
+        }));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("collisions_bruteforce/{}", name));
+    for count in COUNTS {
+        let mut world = world::<F>(count);
+        group.throughput(Throughput::Elements((count * (count - 1) / 2) as u64));
+        group.bench_function(BenchmarkId::from_parameter(count), |b| b.iter(|| {
+            world.resolve_collisions_bruteforce();
+        }));
+    }
+    group.finish();
+}
+
+fn precision(c: &mut Criterion) {
+    bench_precision::<f32>(c, "f32");
+    bench_precision::<f64>(c, "f64");
+}
+
+criterion_group!(benches, precision);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! N-body simulation core: a verlet integrated world of bodies with collisions,
+//! chunked gravity and a set of scenario generators.
+//!
+//! Everything is generic over [`Float`], `f64` by default, `f32` halves the memory traffic,
+//! and over [`Vector`], `Vec2` by default, `Vec3` for 3D worlds.
+//!
+//! The library has no windowing or drawing code, the viewer lives in the `nbodysim-rust` binary,
+//! which runs in `f32` when started with `--f32` and in 3D with `--3d`.
+//! Its dependencies sit behind the default `gui` feature, so depend on the library with
+//! `default-features = false`. The `render` feature alone builds a headless binary.
+//!
+//! ```no_run
+//! use nbodysim_rust::{Vec2, VerletWorld};
+//!
+//! let mut world = VerletWorld::new(0, 540.0);
+//! world.spawn(Vec2::ZERO, Vec2::ZERO, 1000.0, 2.0);
+//! world.update();
+//! ```
+
+#![deny(clippy::all)]
+#![warn(missing_docs)]
+#![allow(clippy::needless_return)]
+#![forbid(unsafe_code)]
+
+mod sim_core;
+
+pub use sim_core::float::Float;
+pub use sim_core::vec2::Vec2;
+pub use sim_core::vec3::Vec3;
+pub use sim_core::vector::Vector;
+pub use sim_core::verlet_object::VerletObject;
+pub use sim_core::verlet_world::VerletWorld;
+pub use sim_core::chunk_tuner::{ChunkSizing, ChunkTuner};
+pub use sim_core::emitter::Emitter;
+pub use sim_core::sink::{Region, Sink};
+pub use sim_core::potential::Potential;
+pub use sim_core::species::Species;
+pub use sim_core::tracers::Tracers;
+pub use sim_core::metrics::{MetricsWriter, StepMetrics};
+pub use sim_core::profile::{Histogram, Phase, Profile};
+pub use sim_core::particles::Particles;
+pub use sim_core::chunk::Chunk;
+pub use sim_core::scenario::Scenario;
+pub use sim_core::scenario::plummer::Plummer;
+pub use sim_core::scenario::disk::ExponentialDisk;
+pub use sim_core::scenario::composite::{Component, Composite};
+pub use sim_core::scenario::kepler::{solve_kepler, KeplerSystem, OrbitalElements};
+pub use sim_core::scenario::few_body::{FewBody, Problem, Reference};
+pub use sim_core::scenario::cold_collapse::{ColdCollapse, Shape};
+pub use sim_core::scenario::protoplanetary::ProtoplanetaryDisk;
@@ -1,21 +1,30 @@
 #![deny(clippy::all)]
+#![allow(clippy::needless_return)]
 #![forbid(unsafe_code)]
 
-#![feature(get_many_mut)]
-
-mod sim_core;
-use sim_core::verlet_world::VerletWorld;
-use sim_core::render::{Renderer, draw};
+mod render;
+use std::time::Instant;
+use nbodysim_rust::{ChunkSizing, Emitter, Float, MetricsWriter, Profile, Region, Sink, Vec2, Vec3, Vector, VerletWorld};
+use render::{Renderer, draw};
+#[cfg(feature = "gui")]
+use render::draw_profile;
+#[cfg(feature = "gui")]
+use error_iter::ErrorIter as _;
+#[cfg(feature = "gui")]
 use winit::{
     event::{Event,VirtualKeyCode},
     event_loop::{EventLoop,ControlFlow},
 };
 
+// the whole simulation runs in f64, or in f32 when started with this argument
+const SINGLE_PRECISION_ARG: &str = "--f32";
+// the world is a plane, or 3D when started with this argument
+const THREE_DIMENSIONS_ARG: &str = "--3d";
+
 const CANVAS_WIDTH: u32 = 1920;
 const CANVAS_HEIGHT: u32 = 1080;
 
 const OBJECTS_COUNT: i32 = 2000;
-const MAX_OBJECTS_COUNT: i32 = 1000;
 const WORLD_RADIUS: f64 = 1080.0 / 2.0;
 const SPAWN_WIDTH_BOUND: f64 = 1080.0 / 2.0; // from -x to x
 const SPAWN_HEIGHT_BOUND: f64 = 10.1; // from -y to y
@@ -24,19 +33,157 @@ const OBJECT_MASS_RANGE: std::ops::Range<f64> = 1.0..50.0;
 const OBJECT_RADIUS_RANGE: std::ops::Range<f64> = 0.1..2.0;
 const DRAW_OUTPUT: bool = false;
 const CIRCLED_FILL: bool = false;
+// the emitter keeps topping the world up with bodies like the initial fill
+const EMITTER_RATE: f64 = 1000.0; // bodies per unit of time
+const EMITTER_MAX_POPULATION: usize = 1000;
+const EMITTER_LIFETIME: Option<std::ops::Range<f64>> = None;
+// bodies beyond this distance are removed instead of clamped back, e.g. Some(WORLD_RADIUS)
+const ABSORBING_RADIUS: Option<f64> = None;
+// radians per unit of time the simulated and drawn frame turns at, 0.0 keeps it inertial
+const FRAME_ROTATION: f64 = 0.0;
+// grid cell size, None lets the world tune it from body sizes, density and timings
+const FIXED_CHUNK_SIZE: Option<i32> = None;
+// per-step metrics as CSV, e.g. Some("output/metrics.csv"), set RUST_LOG=debug to see them in the log instead
+const METRICS_OUTPUT: Option<&str> = None;
+// per-phase timing bars and histograms drawn over the simulation
+#[cfg(feature = "gui")]
+const PROFILE_OVERLAY: bool = false;
+#[cfg(not(feature = "gui"))]
+const HEADLESS_STEPS: i32 = 10_000;
+
+fn has_arg(name: &str) -> bool {
+    return std::env::args().skip(1).any(|arg| arg == name);
+}
+
+fn real<F: Float>(range: &std::ops::Range<f64>) -> std::ops::Range<F> {
+    return F::from_f64(range.start)..F::from_f64(range.end);
+}
+
+fn create_world<F: Float, V: Vector<F>>() -> VerletWorld<F, V> {
+    let mut world = VerletWorld::<F, V>::new(OBJECTS_COUNT, F::from_f64(WORLD_RADIUS));
+    if let Some(size) = FIXED_CHUNK_SIZE {
+        world.chunk_sizing = ChunkSizing::Fixed(size);
+    }
+    world.frame_rotation = F::from_f64(FRAME_ROTATION);
+
+    let mut emitter = Emitter::new(V::ZERO, F::from_f64(EMITTER_RATE));
+    emitter.extent = V::from_vec3(Vec3::new(SPAWN_WIDTH_BOUND, SPAWN_HEIGHT_BOUND, SPAWN_HEIGHT_BOUND).cast());
+    // fill's velocity bound is a displacement per sub step
+    emitter.velocity_spread = F::from_f64(OBJECT_INIT_VELOCITY_BOUND) / world.sub_dt();
+    emitter.mass_range = real(&OBJECT_MASS_RANGE);
+    emitter.radius_range = real(&OBJECT_RADIUS_RANGE);
+    emitter.lifetime_range = EMITTER_LIFETIME.as_ref().map(real);
+    emitter.max_population = Some(EMITTER_MAX_POPULATION);
+    world.emitters.push(emitter);
+
+    if let Some(radius) = ABSORBING_RADIUS {
+        world.sinks.push(Sink::new(Region::Beyond { center: V::ZERO, radius: F::from_f64(radius) }));
+    }
+
+    return world;
+}
+
+fn fill<F: Float, V: Vector<F>>(world: &mut VerletWorld<F, V>) {
+    world.fill(
+        F::from_f64(SPAWN_WIDTH_BOUND),
+        F::from_f64(SPAWN_HEIGHT_BOUND),
+        F::from_f64(OBJECT_INIT_VELOCITY_BOUND),
+        real(&OBJECT_MASS_RANGE),
+        real(&OBJECT_RADIUS_RANGE),
+        CIRCLED_FILL,
+    );
+}
+
+fn init_logger() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+}
+
+fn metrics_writer() -> Option<MetricsWriter> {
+    let path = METRICS_OUTPUT?;
+
+    match MetricsWriter::create(path) {
+        Ok(writer) => {
+            log::info!("writing metrics to {}", path);
+            return Some(writer);
+        }
+        Err(err) => {
+            log::error!("opening metrics output {} failed: {}", path, err);
+            return None;
+        }
+    }
+}
+
+// a failed write stops recording instead of failing every step
+fn record<F: Float, V: Vector<F>>(writer: &mut Option<MetricsWriter>, profile: &mut Profile, world: &VerletWorld<F, V>) {
+    profile.record(&world.metrics);
+
+    if let Some(output) = writer {
+        if let Err(err) = output.write(&world.metrics) {
+            log::error!("writing metrics failed: {}", err);
+            *writer = None;
+        }
+    }
+}
 
 fn main() {
+    init_logger();
+    let single_precision = has_arg(SINGLE_PRECISION_ARG);
+    let three_dimensions = has_arg(THREE_DIMENSIONS_ARG);
+    log::info!(
+        "running in {} and {}",
+        if single_precision { "f32" } else { "f64" },
+        if three_dimensions { "3D" } else { "2D" },
+    );
+
+    match (single_precision, three_dimensions) {
+        (false, false) => run::<f64, Vec2<f64>>(),
+        (false, true) => run::<f64, Vec3<f64>>(),
+        (true, false) => run::<f32, Vec2<f32>>(),
+        (true, true) => run::<f32, Vec3<f32>>(),
+    }
+}
+
+#[cfg(not(feature = "gui"))]
+fn run<F: Float, V: Vector<F>>() {
+    let mut metrics = metrics_writer();
+    let mut profile = Profile::new();
+    let mut world = create_world::<F, V>();
+    // without frames to write there is nothing to draw, rendering stays at zero
+    let mut renderer = DRAW_OUTPUT.then(|| Renderer::headless(CANVAS_WIDTH, CANVAS_HEIGHT, DRAW_OUTPUT));
+
+    fill(&mut world);
+    for _step in 0..HEADLESS_STEPS {
+        world.update();
+
+        if let Some(renderer) = renderer.as_mut() {
+            let time = Instant::now();
+            draw(renderer, &mut world);
+            world.metrics.rendering = time.elapsed();
+        }
+        record(&mut metrics, &mut profile, &world);
+    }
+
+    log::info!("profile over {} steps:\n{}", profile.steps(), profile);
+}
+
+#[cfg(feature = "gui")]
+fn run<F: Float, V: Vector<F>>() {
+    let mut metrics = metrics_writer();
+    let mut profile = Profile::new();
     let mut event_loop = EventLoop::new();
-    let mut world = VerletWorld::new(OBJECTS_COUNT, WORLD_RADIUS, MAX_OBJECTS_COUNT);
+    let mut world = create_world::<F, V>();
     let mut renderer = Renderer::new(CANVAS_WIDTH, CANVAS_HEIGHT, &mut event_loop, DRAW_OUTPUT);
 
-    world.fill(SPAWN_WIDTH_BOUND, SPAWN_HEIGHT_BOUND, OBJECT_INIT_VELOCITY_BOUND, OBJECT_MASS_RANGE, OBJECT_RADIUS_RANGE, CIRCLED_FILL);
+    fill(&mut world);
     event_loop.run(move |event, _, control_flow| {
         // Loop iteration
         if let Event::RedrawRequested(_) = event {
             renderer.pixels.frame_mut().copy_from_slice(renderer.drawing.data());
             if let Err(err) = renderer.pixels.render() {
-                println!("ERROR: {:?}", err);
+                log::error!("pixels.render() failed: {}", err);
+                for source in err.sources().skip(1) {
+                    log::error!("  caused by: {}", source);
+                }
                 *control_flow = ControlFlow::Exit;
                 return;
             }
@@ -46,18 +193,29 @@ fn main() {
         if renderer.input.update(&event) {
             // Close events
             if renderer.input.key_pressed(VirtualKeyCode::Escape) || renderer.input.close_requested() {
+                // the event loop exits the process, nothing gets dropped
+                if let Some(Err(err)) = metrics.as_mut().map(|output| output.flush()) {
+                    log::error!("flushing metrics failed: {}", err);
+                }
+                log::info!("profile over {} steps:\n{}", profile.steps(), profile);
                 *control_flow = ControlFlow::Exit;
                 return;
             }
 
+            renderer.camera.handle_input(&renderer.input);
+
             // Update world
             world.update();
 
-            // FIXME: for perf measurement task :^)
-            world.fill(SPAWN_WIDTH_BOUND, SPAWN_HEIGHT_BOUND, OBJECT_INIT_VELOCITY_BOUND, OBJECT_MASS_RANGE, OBJECT_RADIUS_RANGE, CIRCLED_FILL);
-
             // Draw
+            let time = Instant::now();
             draw(&mut renderer, &mut world);
+            world.metrics.rendering = time.elapsed();
+            record(&mut metrics, &mut profile, &world);
+
+            if PROFILE_OVERLAY {
+                draw_profile(&mut renderer, &profile, &world.metrics);
+            }
 
             // Re-render
             renderer.window.request_redraw();
@@ -0,0 +1,296 @@
+#[cfg(feature = "gui")]
+use pixels::{Pixels, SurfaceTexture};
+#[cfg(feature = "gui")]
+use winit::{
+    dpi::LogicalSize,
+    event::VirtualKeyCode,
+    event_loop::EventLoop,
+    window::{WindowBuilder, Window},
+};
+#[cfg(feature = "gui")]
+use winit_input_helper::WinitInputHelper;
+use tiny_skia::{Pixmap, Paint, PremultipliedColorU8, Rect, Transform};
+
+use nbodysim_rust::{Float, Vec3, Vector, VerletWorld};
+#[cfg(feature = "gui")]
+use nbodysim_rust::{Phase, Profile, StepMetrics};
+
+// radians per frame for held arrow keys and per pixel of mouse drag
+#[cfg(feature = "gui")]
+const CAMERA_KEY_SPEED: f64 = 0.02;
+#[cfg(feature = "gui")]
+const CAMERA_DRAG_SPEED: f64 = 0.005;
+
+// profile overlay layout in pixels
+#[cfg(feature = "gui")]
+const OVERLAY_ROW_HEIGHT: f32 = 14.0;
+#[cfg(feature = "gui")]
+const OVERLAY_BAR_WIDTH: f32 = 240.0;
+#[cfg(feature = "gui")]
+const OVERLAY_HISTOGRAM_WIDTH: f32 = 160.0;
+#[cfg(feature = "gui")]
+const OVERLAY_PHASE_COLORS: [(u8, u8, u8); 8] = [
+    (120, 120, 255),
+    (255, 90, 90),
+    (255, 200, 60),
+    (255, 140, 200),
+    (90, 220, 90),
+    (200, 90, 255),
+    (60, 220, 220),
+    (230, 230, 230),
+];
+
+/// Orthographic view of the world, the default looks down the z axis so 2D worlds render as before.
+pub struct Camera {
+    /// Radians around the world z axis.
+    pub yaw: f64,
+    /// Radians of tilt away from the top view.
+    pub pitch: f64,
+    pub zoom: f64,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        Camera {
+            yaw: 0.0,
+            pitch: 0.0,
+            zoom: 1.0,
+        }
+    }
+
+    /// Screen offset from the canvas centre in x and y, depth towards the viewer in z.
+    pub fn project(&self, point: Vec3) -> Vec3 {
+        return point.rotate_z(-self.yaw).rotate_x(-self.pitch) * self.zoom;
+    }
+
+    // arrows and left mouse drag rotate, the wheel zooms
+    #[cfg(feature = "gui")]
+    pub fn handle_input(&mut self, input: &WinitInputHelper) {
+        if input.key_held(VirtualKeyCode::Left) {
+            self.yaw -= CAMERA_KEY_SPEED;
+        }
+        if input.key_held(VirtualKeyCode::Right) {
+            self.yaw += CAMERA_KEY_SPEED;
+        }
+        if input.key_held(VirtualKeyCode::Up) {
+            self.pitch -= CAMERA_KEY_SPEED;
+        }
+        if input.key_held(VirtualKeyCode::Down) {
+            self.pitch += CAMERA_KEY_SPEED;
+        }
+
+        if input.mouse_held(0) {
+            let (dx, dy) = input.mouse_diff();
+            self.yaw += dx as f64 * CAMERA_DRAG_SPEED;
+            self.pitch += dy as f64 * CAMERA_DRAG_SPEED;
+        }
+
+        self.pitch = self.pitch.clamp(-std::f64::consts::PI, std::f64::consts::PI);
+        self.zoom = (self.zoom * f64::powf(1.1, input.scroll_diff() as f64)).clamp(0.05, 50.0);
+    }
+}
+
+pub struct Renderer {
+    #[cfg(feature = "gui")]
+    pub input: WinitInputHelper,
+    #[cfg(feature = "gui")]
+    pub window: Window,
+    #[cfg(feature = "gui")]
+    pub pixels: Pixels,
+    pub drawing: Pixmap,
+    pub camera: Camera,
+    pub width: u32,
+    pub height: u32,
+    pub draw_frames_in_output: bool
+}
+
+impl Renderer {
+    #[cfg(feature = "gui")]
+    pub fn new(width: u32, height: u32, event_loop: &mut EventLoop<()>, draw_frames_in_output: bool) -> Renderer {
+        // let event_loop = EventLoop::new();
+        let window = {
+            let size = LogicalSize::new(width as f64, height as f64);
+            let scaled_size = LogicalSize::new(width as f64, height as f64);
+            WindowBuilder::new()
+                .with_title("nbodysim-rust")
+                .with_inner_size(scaled_size)
+                .with_min_inner_size(size)
+                .build(event_loop)
+                .unwrap()
+        };
+        let window_size = window.inner_size();
+
+        return Renderer {
+            input: WinitInputHelper::new(),
+            drawing: Pixmap::new(width, height).unwrap(),
+            camera: Camera::new(),
+            pixels: Pixels::new(width, height, SurfaceTexture::new(window_size.width, window_size.height, &window)).unwrap(),
+            window,
+            width,
+            height,
+            draw_frames_in_output
+        };
+    }
+
+    // no window, frames only end up in output/ when draw_frames_in_output is set
+    #[cfg(not(feature = "gui"))]
+    pub fn headless(width: u32, height: u32, draw_frames_in_output: bool) -> Renderer {
+        return Renderer {
+            drawing: Pixmap::new(width, height).unwrap(),
+            camera: Camera::new(),
+            width,
+            height,
+            draw_frames_in_output
+        };
+    }
+}
+
+pub fn draw<F: Float, V: Vector<F>>(renderer: &mut Renderer, world: &mut VerletWorld<F, V>) {
+    let center_x = (renderer.width / 2) as f32;
+    let center_y = (renderer.height / 2) as f32;
+
+    // Fill all with black
+    {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 55);
+        paint.anti_alias = false;
+
+        let rect_result = Rect::from_xywh(0.0, 0.0, renderer.width as f32, renderer.height as f32).unwrap();
+        renderer.drawing.fill_rect(rect_result, &paint, Transform::identity(), None);
+    }
+
+    // Draw chunks
+    let chunk_size = world.chunk_size as f64;
+    for chunk in world.chunks.iter() {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(0, 0, 255, 10);
+        paint.anti_alias = false;
+
+        let corner = renderer.camera.project(Vec3::new(chunk.x as f64, chunk.y as f64, chunk.z as f64) * chunk_size);
+        let size = (chunk_size * renderer.camera.zoom) as f32;
+        let rect_result = Rect::from_xywh(center_x + corner.x as f32, center_y + corner.y as f32, size, size);
+        if let Some(rect) = rect_result {
+            renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+    }
+
+    // Draw objects
+    let mut index = 0;
+    for object in world.objects.iter() {
+        let position = renderer.camera.project(object.position.to_vec3().cast());
+        let radius = object.radius.to_f64() * renderer.camera.zoom;
+        let temp = object.temp.to_f64();
+
+        let mut paint = Paint::default();
+        match world.species.get(object.species as usize).and_then(|species| species.color) {
+            Some([r, g, b]) => paint.set_color_rgba8(r, g, b, 230),
+            None => paint.set_color_rgba8(temp as u8, 255 - temp as u8, temp as u8, 230),
+        }
+        paint.anti_alias = false;
+
+        let rect_result = Rect::from_xywh(center_x + position.x as f32 - (radius / 2.0) as f32, center_y + position.y as f32 - (radius / 2.0) as f32, radius as f32, radius as f32);
+
+        if let Some(rect) = rect_result {
+            renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+        } else {
+            log::warn!("rect creating failed for object i={}, x={}, y={}, t={}, r={}", index, position.x, position.y, temp, radius);
+            log::debug!("calculated to rect: x={}, y={}, w={}, h={}", center_x + position.x as f32, center_y + position.y as f32, radius as f32, radius as f32);
+            continue;
+        }
+
+        index += 1;
+    }
+
+    // Draw tracers, one pixel each so millions stay cheap
+    if let Some(color) = PremultipliedColorU8::from_rgba(150, 150, 220, 255) {
+        let width = renderer.width as i64;
+        let height = renderer.height as i64;
+        let pixels = renderer.drawing.pixels_mut();
+        for position in world.tracers.position.iter() {
+            let position = renderer.camera.project(position.to_vec3().cast());
+            let x = (center_x as f64 + position.x) as i64;
+            let y = (center_y as f64 + position.y) as i64;
+            if x >= 0 && x < width && y >= 0 && y < height {
+                pixels[(y * width + x) as usize] = color;
+            }
+        }
+    }
+
+    // Save result to file
+    if renderer.draw_frames_in_output {
+        let mut fname = "output/image_".to_owned();
+        fname.push_str(&format!("{:0>8}", world.step.to_string()));
+        fname.push_str(".png");
+
+        renderer.drawing.save_png(fname).unwrap();
+    }
+}
+
+/// Draws one row per phase in the top left corner, each with a bar of the last step's time
+/// and the phase's histogram, bars share a scale of twice the p99 frame and rendering time.
+#[cfg(feature = "gui")]
+pub fn draw_profile(renderer: &mut Renderer, profile: &Profile, metrics: &StepMetrics) {
+    let margin = 8.0;
+    let swatch = OVERLAY_ROW_HEIGHT - 4.0;
+    let width = swatch + OVERLAY_BAR_WIDTH + OVERLAY_HISTOGRAM_WIDTH + 4.0 * margin;
+    let height = OVERLAY_ROW_HEIGHT * Phase::ALL.len() as f32 + 2.0 * margin;
+
+    // opaque backdrop, the simulation fades old frames but the overlay must not smear
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(10, 10, 10, 255);
+    paint.anti_alias = false;
+    if let Some(rect) = Rect::from_xywh(0.0, 0.0, width, height) {
+        renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+    }
+
+    let scale = 2.0 * profile.histogram(Phase::Frame).percentile(0.99)
+        .max(profile.histogram(Phase::Rendering).percentile(0.99))
+        .as_secs_f32();
+
+    // histograms share the span of non-empty buckets over all phases
+    let first_bucket = Phase::ALL.iter()
+        .filter_map(|phase| profile.histogram(*phase).buckets.iter().position(|count| *count > 0))
+        .min()
+        .unwrap_or(0);
+    let last_bucket = Phase::ALL.iter()
+        .filter_map(|phase| profile.histogram(*phase).buckets.iter().rposition(|count| *count > 0))
+        .max()
+        .unwrap_or(0);
+    let column_width = OVERLAY_HISTOGRAM_WIDTH / (last_bucket - first_bucket + 1) as f32;
+
+    for (row, phase) in Phase::ALL.iter().enumerate() {
+        let (r, g, b) = OVERLAY_PHASE_COLORS[row];
+        let y = margin + row as f32 * OVERLAY_ROW_HEIGHT;
+        let mut x = margin;
+
+        paint.set_color_rgba8(r, g, b, 255);
+        if let Some(rect) = Rect::from_xywh(x, y, swatch, swatch) {
+            renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+        x += swatch + margin;
+
+        // last step
+        paint.set_color_rgba8(r, g, b, 200);
+        if scale > 0.0 {
+            let bar = (metrics.phase(*phase).as_secs_f32() / scale).min(1.0) * OVERLAY_BAR_WIDTH;
+            if let Some(rect) = Rect::from_xywh(x, y, bar, swatch) {
+                renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+            }
+        }
+        x += OVERLAY_BAR_WIDTH + margin;
+
+        // distribution, tallest bucket fills the row
+        let histogram = profile.histogram(*phase);
+        let tallest = histogram.buckets.iter().max().copied().unwrap_or(0);
+        if tallest == 0 {
+            continue;
+        }
+        for bucket in first_bucket..=last_bucket {
+            let column = histogram.buckets[bucket] as f32 / tallest as f32 * swatch;
+            let column_x = x + (bucket - first_bucket) as f32 * column_width;
+            if let Some(rect) = Rect::from_xywh(column_x, y + swatch - column, column_width.max(1.0), column) {
+                renderer.drawing.fill_rect(rect, &paint, Transform::identity(), None);
+            }
+        }
+    }
+}
@@ -1,5 +1,19 @@
-pub mod point;
+//! Simulation core, free of any windowing or rendering code.
+
+pub mod float;
+pub mod vec2;
+pub mod vec3;
+pub mod vector;
 pub mod chunk;
+pub mod chunk_tuner;
+pub mod emitter;
+pub mod sink;
+pub mod species;
+pub mod tracers;
 pub mod verlet_object;
+pub mod particles;
+pub mod potential;
 pub mod verlet_world;
-pub mod render;
+pub mod metrics;
+pub mod profile;
+pub mod scenario;
@@ -1,10 +1,213 @@
-use super::point::Point;
+use std::collections::HashMap;
 
-pub struct Chunk {
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
+
+/// Cell of the spatial grid, rebuilt by `VerletWorld` every sub step. `z` is always zero in 2D worlds.
+pub struct Chunk<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Grid coordinates, the chunk spans `x * chunk_size` to `(x + 1) * chunk_size`.
     pub x: i32,
+    /// Grid coordinate along y.
     pub y: i32,
-    pub indecies: Vec<i32>,
+    /// Grid coordinate along z.
+    pub z: i32,
+    /// Indices into `VerletWorld::objects`, only valid until bodies are removed, see `Particles::index_of`.
+    pub indecies: Vec<usize>,
+
+    /// Centre of the gravity sources in the chunk, weighted by their mass.
+    pub mass_center: V,
+    /// Sum of the source masses in the chunk.
+    pub mass: F,
+}
+
+// level of the node above the last level that still merged, it holds every cell
+const ROOT_LEVEL: u32 = u32::MAX;
+
+/// Hierarchy over the chunks for gravity, level 0 nodes are the chunks themselves and each level
+/// above merges blocks of 2 x 2 (x 2) nodes of the one below, up to a single root.
+///
+/// `walk` splits the tree for a box into chunks close enough to sum body by body and nodes far
+/// enough to pull as their mass at their centre of mass, Barnes & Hut (1986) on the grid's cells.
+pub(crate) struct ChunkTree<F: Float> {
+    nodes: Vec<TreeNode<F>>,
+    chunk_size: F,
+}
+
+struct TreeNode<F: Float> {
+    level: u32,
+    cell: (i32, i32, i32),
+    mass: F,
+    center: Vec3<F>,
+    children: Vec<usize>,
+    // index into the chunks on level 0
+    chunk: usize,
+}
+
+impl<F: Float> ChunkTree<F> {
+    pub(crate) fn new<V: Vector<F>>(chunks: &[Chunk<F, V>], chunk_size: i32) -> ChunkTree<F> {
+        let mut nodes: Vec<TreeNode<F>> = chunks.iter().enumerate().map(|(index, chunk)| TreeNode {
+            level: 0,
+            cell: (chunk.x, chunk.y, chunk.z),
+            mass: chunk.mass,
+            center: chunk.mass_center.to_vec3(),
+            children: Vec::new(),
+            chunk: index,
+        }).collect();
+
+        let mut level_start = 0;
+        let mut level = 0;
+        while nodes.len() - level_start > 1 {
+            let level_end = nodes.len();
+
+            // cells either side of zero end up at -1 and 0 and never merge by halving
+            let settled = nodes[level_start..level_end].iter().all(|node| {
+                let (x, y, z) = node.cell;
+                x >> 1 == x && y >> 1 == y && z >> 1 == z
+            });
+            if settled {
+                nodes.push(TreeNode {
+                    level: ROOT_LEVEL,
+                    cell: (0, 0, 0),
+                    mass: F::ZERO,
+                    center: Vec3::ZERO,
+                    children: Vec::new(),
+                    chunk: usize::MAX,
+                });
+                let root = nodes.len() - 1;
+                for child in level_start..level_end {
+                    add_child(&mut nodes, root, child);
+                }
+                break;
+            }
+
+            let mut parents: HashMap<(i32, i32, i32), usize> = HashMap::new();
+            for child in level_start..level_end {
+                let (x, y, z) = nodes[child].cell;
+                let cell = (x >> 1, y >> 1, z >> 1);
+                let parent = match parents.get(&cell) {
+                    Some(parent) => *parent,
+                    None => {
+                        nodes.push(TreeNode {
+                            level: level + 1,
+                            cell,
+                            mass: F::ZERO,
+                            center: Vec3::ZERO,
+                            children: Vec::new(),
+                            chunk: usize::MAX,
+                        });
+                        parents.insert(cell, nodes.len() - 1);
+                        nodes.len() - 1
+                    }
+                };
+
+                add_child(&mut nodes, parent, child);
+            }
+
+            level_start = level_end;
+            level += 1;
+        }
+
+        return ChunkTree {
+            nodes,
+            chunk_size: F::from_f64(f64::from(chunk_size)),
+        };
+    }
+
+    /// Moves the level 0 nodes to the `(mass, center)` of their chunks' bodies now and every node
+    /// above along with them, keeping the grouping the tree was built with.
+    pub(crate) fn refit(&mut self, chunks: &[(F, Vec3<F>)]) {
+        for index in 0..self.nodes.len() {
+            if self.nodes[index].level == 0 {
+                (self.nodes[index].mass, self.nodes[index].center) = chunks[self.nodes[index].chunk];
+                continue;
+            }
+
+            let mut mass = F::ZERO;
+            let mut center = Vec3::ZERO;
+            // children always come before their parent
+            for (order, child) in self.nodes[index].children.iter().enumerate() {
+                (mass, center) = merge(mass, center, order == 0, &self.nodes[*child]);
+            }
+            (self.nodes[index].mass, self.nodes[index].center) = (mass, center);
+        }
+    }
+
+    /// Mass and centre of mass of the node at `index`.
+    pub(crate) fn point_mass(&self, index: usize) -> (F, Vec3<F>) {
+        return (self.nodes[index].mass, self.nodes[index].center);
+    }
+
+    /// Sorts the tree for the box `min..max` inside the level 0 `cell` into the `near` chunks
+    /// and the `far` nodes, see `point_mass`. A node is far when its width is below `opening_angle`
+    /// times its centre of mass's distance from the box, and never when it holds the box's cell.
+    pub(crate) fn walk(
+        &self,
+        cell: (i32, i32, i32),
+        min: Vec3<F>,
+        max: Vec3<F>,
+        opening_angle: F,
+        near: &mut Vec<usize>,
+        far: &mut Vec<usize>,
+    ) {
+        let Some(root) = self.nodes.len().checked_sub(1) else {
+            return;
+        };
+
+        let mut stack = vec![root];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.mass == F::ZERO {
+                continue;
+            }
+
+            if !node.holds(cell) {
+                let width = self.chunk_size * F::from_f64(2.0_f64.powi(node.level as i32));
+                let gap = Vec3::new(
+                    (min.x - node.center.x).max(node.center.x - max.x).max(F::ZERO),
+                    (min.y - node.center.y).max(node.center.y - max.y).max(F::ZERO),
+                    (min.z - node.center.z).max(node.center.z - max.z).max(F::ZERO),
+                );
+                if width * width < opening_angle * opening_angle * gap.length_square() {
+                    far.push(index);
+                    continue;
+                }
+            }
+
+            if node.level == 0 {
+                near.push(node.chunk);
+            } else {
+                stack.extend(node.children.iter().copied());
+            }
+        }
+    }
+}
+
+impl<F: Float> TreeNode<F> {
+    fn holds(&self, cell: (i32, i32, i32)) -> bool {
+        if self.level == ROOT_LEVEL {
+            return true;
+        }
+
+        return (cell.0 >> self.level, cell.1 >> self.level, cell.2 >> self.level) == self.cell;
+    }
+}
+
+fn add_child<F: Float>(nodes: &mut [TreeNode<F>], parent: usize, child: usize) {
+    let (mass, center) = merge(nodes[parent].mass, nodes[parent].center, nodes[parent].children.is_empty(), &nodes[child]);
+    let node = &mut nodes[parent];
+
+    (node.mass, node.center) = (mass, center);
+    node.children.push(child);
+}
+
+// massless children leave the centre alone unless nothing placed it yet
+fn merge<F: Float>(mass: F, center: Vec3<F>, first: bool, child: &TreeNode<F>) -> (F, Vec3<F>) {
+    let center = if first { child.center } else { center };
+    if child.mass > F::ZERO {
+        return (mass + child.mass, (center * mass + child.center * child.mass) / (mass + child.mass));
+    }
 
-    pub mass_center: Point,
-    pub mass: f64,
+    return (mass, center);
 }
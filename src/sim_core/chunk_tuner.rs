@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+use super::metrics::StepMetrics;
+
+// target occupancy moves by this factor per tuning window
+const OCCUPANCY_STEP: f64 = 1.25;
+
+/// How `VerletWorld` picks `chunk_size` before each update.
+pub enum ChunkSizing {
+    /// Always this size, below the largest body diameter contacts more than one cell apart get missed.
+    Fixed(i32),
+    /// Tuned every update by the `ChunkTuner`.
+    Auto(ChunkTuner),
+}
+
+/// Picks the grid cell size from the largest body, the current occupancy of cells
+/// and the measured cost of grid build plus collisions.
+///
+/// Cells are sized so that on average `occupancy` bodies share one, and every `interval`
+/// steps that target moves up or down, reversing whenever the cost per body got worse.
+pub struct ChunkTuner {
+    /// Smallest cell size the tuner goes down to, the largest body diameter still wins.
+    pub min_size: i32,
+    /// Largest cell size the tuner goes up to.
+    pub max_size: i32,
+    /// Steps to average timings over between occupancy adjustments.
+    pub interval: i32,
+    /// Target mean count of bodies per occupied cell.
+    pub occupancy: f64,
+    /// Bounds `occupancy` stays within while it moves.
+    pub occupancy_range: Range<f64>,
+
+    direction: f64,
+    window_cost: f64,
+    window_steps: i32,
+    last_cost: Option<f64>,
+}
+
+impl Default for ChunkTuner {
+    fn default() -> Self {
+        return ChunkTuner::new();
+    }
+}
+
+impl ChunkTuner {
+    /// Starts at four bodies per cell between sizes 2 and 48.
+    pub fn new() -> ChunkTuner {
+        ChunkTuner {
+            min_size: 2,
+            max_size: 48,
+            interval: 10,
+            occupancy: 4.0,
+            occupancy_range: 1.0..64.0,
+
+            direction: 1.0,
+            window_cost: 0.0,
+            window_steps: 0,
+            last_cost: None,
+        }
+    }
+
+    /// Size for the next step given the current one, `metrics` of the last step
+    /// and the cells it produced in a `dim` dimensional world.
+    pub fn tune(
+        &mut self,
+        chunk_size: i32,
+        max_radius: f64,
+        object_count: usize,
+        chunk_count: usize,
+        dim: usize,
+        metrics: &StepMetrics
+    ) -> i32 {
+        // touching bodies are less than two radii apart on every axis, cells at least that wide keep them
+        // in the same or adjacent cells, diagonals included, which is all the collision pass looks at
+        let min_size = self.min_size.max((2.0 * max_radius).ceil() as i32).max(1);
+        let max_size = self.max_size.max(min_size);
+
+        if object_count == 0 || chunk_count == 0 {
+            return chunk_size.clamp(min_size, max_size);
+        }
+
+        self.window_cost += (metrics.grid + metrics.collisions).as_secs_f64() / object_count as f64;
+        self.window_steps += 1;
+
+        if self.window_steps >= self.interval {
+            let cost = self.window_cost / self.window_steps as f64;
+            if let Some(last_cost) = self.last_cost {
+                if cost > last_cost {
+                    self.direction = -self.direction;
+                }
+            }
+
+            self.last_cost = Some(cost);
+            self.window_cost = 0.0;
+            self.window_steps = 0;
+
+            self.occupancy = (self.occupancy * OCCUPANCY_STEP.powf(self.direction))
+                .clamp(self.occupancy_range.start, self.occupancy_range.end);
+        }
+
+        // occupancy grows with cell volume, half the correction per step keeps the size from ringing
+        let occupancy = object_count as f64 / chunk_count as f64;
+        let size = chunk_size as f64 * (self.occupancy / occupancy).powf(0.5 / dim as f64);
+
+        return (size.round() as i32).clamp(min_size, max_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::vec2::Vec2;
+    use super::super::verlet_world::VerletWorld;
+
+    #[test]
+    fn never_below_largest_diameter() {
+        let mut tuner = ChunkTuner::new();
+        let metrics = StepMetrics::default();
+
+        // crowded cells ask for a smaller size every step
+        let mut chunk_size = 40;
+        for _step in 0..100 {
+            chunk_size = tuner.tune(chunk_size, 7.5, 10_000, 10, 2, &metrics);
+            assert!(chunk_size >= 15, "size {chunk_size} below the largest diameter");
+        }
+        assert_eq!(chunk_size, 15);
+    }
+
+    #[test]
+    fn fixed_size_is_left_alone() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        world.chunk_sizing = ChunkSizing::Fixed(7);
+        for i in 0..40 {
+            world.spawn(Vec2::new(i as f64 * 25.0 - 490.0, 0.0), Vec2::ZERO, 1.0, 10.0);
+        }
+
+        for _step in 0..20 {
+            world.update();
+            assert_eq!(world.chunk_size, 7);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::ops::Range;
+use rand::Rng;
+
+use super::float::Float;
+use super::scenario::Scenario;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
+use super::verlet_object::VerletObject;
+use super::verlet_world::VerletWorld;
+
+/// Continuous source of bodies, run by `VerletWorld::update` once per step.
+///
+/// Bodies appear uniformly in the box of half size `extent` around `position`, with `velocity`
+/// plus a uniform per-axis offset of up to `velocity_spread`. Empty ranges give their start value.
+#[derive(Clone, Debug)]
+pub struct Emitter<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Centre of the spawn box.
+    pub position: V,
+    /// Half size of the spawn box per axis.
+    pub extent: V,
+    /// Bodies per unit of simulated time.
+    pub rate: F,
+    /// Mean velocity of spawned bodies.
+    pub velocity: V,
+    /// Largest per-axis offset from `velocity`.
+    pub velocity_spread: F,
+    /// Masses are drawn uniformly from this range.
+    pub mass_range: Range<F>,
+    /// Radii are drawn uniformly from this range.
+    pub radius_range: Range<F>,
+    /// Simulated time each body lives, `None` keeps them forever.
+    pub lifetime_range: Option<Range<F>>,
+    /// Pauses while the world holds at least this many bodies.
+    pub max_population: Option<usize>,
+    /// Stops for good after spawning this many bodies.
+    pub max_emitted: Option<usize>,
+    /// Given to every spawned body.
+    pub species: u32,
+
+    /// Bodies spawned so far.
+    pub emitted: usize,
+    // fraction of a body owed from earlier steps
+    pending: F,
+}
+
+impl<F: Float, V: Vector<F>> Emitter<F, V> {
+    /// Point source of unit bodies at rest.
+    pub fn new(position: V, rate: F) -> Emitter<F, V> {
+        Emitter {
+            position,
+            extent: V::ZERO,
+            rate,
+            velocity: V::ZERO,
+            velocity_spread: F::ZERO,
+            mass_range: F::ONE..F::ONE,
+            radius_range: F::ONE..F::ONE,
+            lifetime_range: None,
+            max_population: None,
+            max_emitted: None,
+            species: 0,
+
+            emitted: 0,
+            pending: F::ZERO,
+        }
+    }
+
+    /// Number of bodies to spawn over a step of `dt` in a world of `population` bodies.
+    /// Bodies held back by a cap are dropped rather than released in a burst later.
+    pub fn due(&mut self, dt: F, population: usize) -> usize {
+        self.pending += self.rate * dt;
+        let owed = self.pending.floor();
+        self.pending -= owed;
+
+        let mut count = owed.to_f64() as usize;
+        if let Some(max_population) = self.max_population {
+            count = count.min(max_population.saturating_sub(population));
+        }
+        if let Some(max_emitted) = self.max_emitted {
+            count = count.min(max_emitted.saturating_sub(self.emitted));
+        }
+
+        self.emitted += count;
+        return count;
+    }
+
+    /// Draws one body, `dt` is the integration step its velocity is set up for.
+    pub fn sample<R: Rng>(&self, rnd: &mut R, dt: F) -> VerletObject<F, V> {
+        let extent = self.extent.to_vec3();
+        let offset = Vec3::new(
+            sample(rnd, -extent.x..extent.x),
+            sample(rnd, -extent.y..extent.y),
+            sample(rnd, -extent.z..extent.z),
+        );
+        let spread = -self.velocity_spread..self.velocity_spread;
+        let velocity = Vec3::new(
+            sample(rnd, spread.clone()),
+            sample(rnd, spread.clone()),
+            sample(rnd, spread),
+        );
+
+        let mut object = VerletObject::with_velocity(
+            self.position + V::from_vec3(offset),
+            self.velocity + V::from_vec3(velocity),
+            sample(rnd, self.mass_range.clone()),
+            sample(rnd, self.radius_range.clone()),
+            dt,
+        );
+        object.species = self.species;
+        if let Some(lifetime_range) = &self.lifetime_range {
+            object.lifetime = sample(rnd, lifetime_range.clone());
+        }
+
+        return object;
+    }
+}
+
+// scenarios add emitters alongside their initial bodies
+impl<F: Float, V: Vector<F>> Scenario<F, V> for Emitter<F, V> {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        world.emitters.push(self.clone());
+    }
+}
+
+// gen_range panics on an empty range
+pub(crate) fn sample<F: Float, R: Rng>(rnd: &mut R, range: Range<F>) -> F {
+    if range.start < range.end {
+        return rnd.gen_range(range);
+    }
+
+    return range.start;
+}
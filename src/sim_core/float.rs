@@ -0,0 +1,115 @@
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use rand::distributions::uniform::SampleUniform;
+
+/// Floating point type the simulation runs in, implemented for `f32` and `f64`.
+pub trait Float:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + PartialOrd
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + SampleUniform
+{
+    /// Additive identity.
+    const ZERO: Self;
+    /// Multiplicative identity.
+    const ONE: Self;
+    /// Positive infinity, e.g. the lifetime of bodies that never expire.
+    const INFINITY: Self;
+
+    /// Converts, rounding to the nearest value for `f32`.
+    fn from_f64(v: f64) -> Self;
+    /// Widens losslessly.
+    fn to_f64(self) -> f64;
+
+    /// Square root.
+    fn sqrt(self) -> Self;
+    /// Absolute value.
+    fn abs(self) -> Self;
+    /// Largest integer not above the value.
+    fn floor(self) -> Self;
+    /// Sine and cosine in one call.
+    fn sin_cos(self) -> (Self, Self);
+    /// Neither zero, subnormal, infinite nor NaN.
+    fn is_normal(self) -> bool;
+    /// Neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+    /// Restricts the value to `min..=max`.
+    fn clamp(self, min: Self, max: Self) -> Self;
+    /// Larger of the two, a NaN loses.
+    fn max(self, other: Self) -> Self;
+    /// Smaller of the two, a NaN loses.
+    fn min(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            const ZERO: $t = 0.0;
+            const ONE: $t = 1.0;
+            const INFINITY: $t = <$t>::INFINITY;
+
+            fn from_f64(v: f64) -> $t {
+                return v as $t;
+            }
+
+            fn to_f64(self) -> f64 {
+                return self as f64;
+            }
+
+            fn sqrt(self) -> $t {
+                return <$t>::sqrt(self);
+            }
+
+            fn abs(self) -> $t {
+                return <$t>::abs(self);
+            }
+
+            fn floor(self) -> $t {
+                return <$t>::floor(self);
+            }
+
+            fn sin_cos(self) -> ($t, $t) {
+                return <$t>::sin_cos(self);
+            }
+
+            fn is_normal(self) -> bool {
+                return <$t>::is_normal(self);
+            }
+
+            fn is_finite(self) -> bool {
+                return <$t>::is_finite(self);
+            }
+
+            fn clamp(self, min: $t, max: $t) -> $t {
+                return <$t>::clamp(self, min, max);
+            }
+
+            fn max(self, other: $t) -> $t {
+                return <$t>::max(self, other);
+            }
+
+            fn min(self, other: $t) -> $t {
+                return <$t>::min(self, other);
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use super::profile::Phase;
+
+/// Counters and phase timings of one `VerletWorld::update`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StepMetrics {
+    /// Updates so far, this one included.
+    pub step: i32,
+    /// Grid cell size the step ran with.
+    pub chunk_size: i32,
+    /// Occupied grid cells at the end of the step.
+    pub chunk_count: usize,
+    /// Live bodies after the step.
+    pub object_count: usize,
+    /// Live tracers after the step.
+    pub tracer_count: usize,
+
+    /// Chunk grid rebuilds, summed over all sub steps.
+    pub grid: Duration,
+    /// Collision passes, summed over all sub steps.
+    pub collisions: Duration,
+    /// Chunk gravity and external potentials, summed over all sub steps.
+    pub gravity: Duration,
+    /// Tracer gravity and integration.
+    pub tracers: Duration,
+    /// Verlet integration, summed over all sub steps.
+    pub integration: Duration,
+    /// Constraint pass after the sub steps.
+    pub constraints: Duration,
+    /// Drawing the step, left at zero by the world and filled in by whoever renders it.
+    pub rendering: Duration,
+    /// Whole update, including the phases above except rendering.
+    pub frame: Duration,
+}
+
+impl StepMetrics {
+    /// Column names of `to_csv`.
+    pub const CSV_HEADER: &'static str =
+        "step,chunk_size,chunk_count,object_count,tracer_count,grid_us,collisions_us,gravity_us,tracers_us,integration_us,constraints_us,rendering_us,frame_us";
+
+    /// One CSV row matching `CSV_HEADER`, timings in microseconds.
+    pub fn to_csv(&self) -> String {
+        return format!(
+            "{},{},{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3}",
+            self.step,
+            self.chunk_size,
+            self.chunk_count,
+            self.object_count,
+            self.tracer_count,
+            micros(self.grid),
+            micros(self.collisions),
+            micros(self.gravity),
+            micros(self.tracers),
+            micros(self.integration),
+            micros(self.constraints),
+            micros(self.rendering),
+            micros(self.frame),
+        );
+    }
+
+    /// Timing of one phase, `Phase::Frame` is the whole update.
+    pub fn phase(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Grid => self.grid,
+            Phase::Collisions => self.collisions,
+            Phase::Gravity => self.gravity,
+            Phase::Tracers => self.tracers,
+            Phase::Integration => self.integration,
+            Phase::Constraints => self.constraints,
+            Phase::Rendering => self.rendering,
+            Phase::Frame => self.frame,
+        }
+    }
+}
+
+// key=value pairs, so log lines stay greppable
+impl fmt::Display for StepMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "step={} chunk_size={} chunk_count={} object_count={} tracer_count={} grid={:?} collisions={:?} gravity={:?} tracers={:?} integration={:?} constraints={:?} frame={:?}",
+            self.step,
+            self.chunk_size,
+            self.chunk_count,
+            self.object_count,
+            self.tracer_count,
+            self.grid,
+            self.collisions,
+            self.gravity,
+            self.tracers,
+            self.integration,
+            self.constraints,
+            self.frame,
+        );
+    }
+}
+
+/// Writes `StepMetrics` as CSV, one row per step after a header line.
+pub struct MetricsWriter<W: Write = BufWriter<File>> {
+    writer: W,
+}
+
+impl MetricsWriter {
+    /// Creates or truncates the file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<MetricsWriter> {
+        return MetricsWriter::new(BufWriter::new(File::create(path)?));
+    }
+}
+
+impl<W: Write> MetricsWriter<W> {
+    /// Writes the header line right away.
+    pub fn new(mut writer: W) -> io::Result<MetricsWriter<W>> {
+        writeln!(writer, "{}", StepMetrics::CSV_HEADER)?;
+        return Ok(MetricsWriter { writer });
+    }
+
+    /// Appends the row for one step.
+    pub fn write(&mut self, metrics: &StepMetrics) -> io::Result<()> {
+        return writeln!(self.writer, "{}", metrics.to_csv());
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        return self.writer.flush();
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    return duration.as_secs_f64() * 1_000_000.0;
+}
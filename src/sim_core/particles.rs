@@ -0,0 +1,796 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
+use super::verlet_object::VerletObject;
+
+// independent accumulators per lane let the compiler vectorize the force loops
+const LANES: usize = 8;
+
+/// Structure of arrays body store, every field is its own array and all arrays share the same length.
+/// The `z` arrays are only filled in 3D worlds and stay empty in 2D ones.
+///
+/// Kernels work on whole arrays, while `get`, `set`, `modify` and `iter` give a per-body
+/// `VerletObject` view for code that thinks in bodies.
+///
+/// Every pushed body gets a stable `id`, which `index_of` maps back to its current index
+/// once removals have moved bodies around. Ids, `species` and `user_data` travel with the body
+/// through removals, `get` and `set`, and pushing a copy back restores its id.
+#[derive(Clone, Default)]
+pub struct Particles<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Per body `VerletObject::position` components.
+    pub position_x: Vec<F>,
+    /// Per body `VerletObject::position` components.
+    pub position_y: Vec<F>,
+    /// Per body `VerletObject::position` components.
+    pub position_z: Vec<F>,
+    /// Per body `VerletObject::position_last` components.
+    pub position_last_x: Vec<F>,
+    /// Per body `VerletObject::position_last` components.
+    pub position_last_y: Vec<F>,
+    /// Per body `VerletObject::position_last` components.
+    pub position_last_z: Vec<F>,
+    /// Per body `VerletObject::acceleration` components.
+    pub acceleration_x: Vec<F>,
+    /// Per body `VerletObject::acceleration` components.
+    pub acceleration_y: Vec<F>,
+    /// Per body `VerletObject::acceleration` components.
+    pub acceleration_z: Vec<F>,
+    /// Per body `VerletObject::mass`.
+    pub mass: Vec<F>,
+    /// Per body `VerletObject::radius`.
+    pub radius: Vec<F>,
+    /// Per body `VerletObject::temp`.
+    pub temp: Vec<F>,
+    /// Per body `VerletObject::friction_factor`.
+    pub friction_factor: Vec<F>,
+    /// Per body `VerletObject::fixed`.
+    pub fixed: Vec<bool>,
+    /// Per body `VerletObject::lifetime`.
+    pub lifetime: Vec<F>,
+    /// Per body `VerletObject::species`.
+    pub species: Vec<u32>,
+    /// Per body `VerletObject::user_data`.
+    pub user_data: Vec<u64>,
+
+    id: Vec<u64>,
+    index: HashMap<u64, usize>,
+    next_id: u64,
+    vector: PhantomData<V>,
+}
+
+impl<F: Float, V: Vector<F>> Particles<F, V> {
+    /// Empty store.
+    pub fn new() -> Particles<F, V> {
+        return Particles::default();
+    }
+
+    /// Number of bodies.
+    pub fn len(&self) -> usize {
+        return self.position_x.len();
+    }
+
+    /// Whether there are no bodies.
+    pub fn is_empty(&self) -> bool {
+        return self.position_x.is_empty();
+    }
+
+    /// Appends a body, keeping `object.id` when it is set and not taken, otherwise under a new id.
+    pub fn push(&mut self, object: VerletObject<F, V>) -> &mut Self {
+        let position = object.position.to_vec3();
+        let position_last = object.position_last.to_vec3();
+        let acceleration = object.acceleration.to_vec3();
+
+        self.position_x.push(position.x);
+        self.position_y.push(position.y);
+        self.position_last_x.push(position_last.x);
+        self.position_last_y.push(position_last.y);
+        self.acceleration_x.push(acceleration.x);
+        self.acceleration_y.push(acceleration.y);
+        if V::DIM == 3 {
+            self.position_z.push(position.z);
+            self.position_last_z.push(position_last.z);
+            self.acceleration_z.push(acceleration.z);
+        }
+        self.mass.push(object.mass);
+        self.radius.push(object.radius);
+        self.temp.push(object.temp);
+        self.friction_factor.push(object.friction_factor);
+        self.fixed.push(object.fixed);
+        self.lifetime.push(object.lifetime);
+        self.species.push(object.species);
+        self.user_data.push(object.user_data);
+
+        // ids start at 1, zero marks a body that was never pushed
+        let id = if object.id != 0 && !self.index.contains_key(&object.id) {
+            object.id
+        } else {
+            self.next_id + 1
+        };
+        self.next_id = self.next_id.max(id);
+        self.index.insert(id, self.id.len());
+        self.id.push(id);
+
+        return self;
+    }
+
+    /// Copy of the body at `index`.
+    pub fn get(&self, index: usize) -> VerletObject<F, V> {
+        return VerletObject {
+            position: self.position(index),
+            position_last: self.position_last(index),
+            acceleration: vector(&self.acceleration_x, &self.acceleration_y, &self.acceleration_z, index),
+            mass: self.mass[index],
+            radius: self.radius[index],
+            temp: self.temp[index],
+            friction_factor: self.friction_factor[index],
+            fixed: self.fixed[index],
+            lifetime: self.lifetime[index],
+            id: self.id[index],
+            species: self.species[index],
+            user_data: self.user_data[index],
+        };
+    }
+
+    /// Overwrites the body at `index`, keeping its id.
+    pub fn set(&mut self, index: usize, object: &VerletObject<F, V>) -> &mut Self {
+        self.set_position(index, object.position);
+        self.set_position_last(index, object.position_last);
+        set_vector(&mut self.acceleration_x, &mut self.acceleration_y, &mut self.acceleration_z, index, object.acceleration);
+        self.mass[index] = object.mass;
+        self.radius[index] = object.radius;
+        self.temp[index] = object.temp;
+        self.friction_factor[index] = object.friction_factor;
+        self.fixed[index] = object.fixed;
+        self.lifetime[index] = object.lifetime;
+        self.species[index] = object.species;
+        self.user_data[index] = object.user_data;
+
+        return self;
+    }
+
+    /// Removes the body at `index` and moves the last body into its place.
+    pub fn swap_remove(&mut self, index: usize) -> VerletObject<F, V> {
+        let object = self.get(index);
+
+        self.index.remove(&object.id);
+        self.id.swap_remove(index);
+        if let Some(moved) = self.id.get(index) {
+            self.index.insert(*moved, index);
+        }
+
+        self.position_x.swap_remove(index);
+        self.position_y.swap_remove(index);
+        self.position_last_x.swap_remove(index);
+        self.position_last_y.swap_remove(index);
+        self.acceleration_x.swap_remove(index);
+        self.acceleration_y.swap_remove(index);
+        if V::DIM == 3 {
+            self.position_z.swap_remove(index);
+            self.position_last_z.swap_remove(index);
+            self.acceleration_z.swap_remove(index);
+        }
+        self.mass.swap_remove(index);
+        self.radius.swap_remove(index);
+        self.temp.swap_remove(index);
+        self.friction_factor.swap_remove(index);
+        self.fixed.swap_remove(index);
+        self.lifetime.swap_remove(index);
+        self.species.swap_remove(index);
+        self.user_data.swap_remove(index);
+
+        return object;
+    }
+
+    /// Edits the body at `index` through a `VerletObject` and writes it back.
+    pub fn modify<R>(&mut self, index: usize, edit: impl FnOnce(&mut VerletObject<F, V>) -> R) -> R {
+        let mut object = self.get(index);
+        let result = edit(&mut object);
+        self.set(index, &object);
+
+        return result;
+    }
+
+    /// Stable id of the body at `index`.
+    pub fn id(&self, index: usize) -> u64 {
+        return self.id[index];
+    }
+
+    /// Current index of the body with `id`, `None` once it was removed.
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        return self.index.get(&id).copied();
+    }
+
+    /// Copies of every body, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = VerletObject<F, V>> + '_ {
+        return (0..self.len()).map(|index| self.get(index));
+    }
+
+    /// Position of the body at `index`.
+    pub fn position(&self, index: usize) -> V {
+        return vector(&self.position_x, &self.position_y, &self.position_z, index);
+    }
+
+    /// Moves the body at `index` without changing its velocity.
+    pub fn set_position(&mut self, index: usize, position: V) {
+        set_vector(&mut self.position_x, &mut self.position_y, &mut self.position_z, index, position);
+    }
+
+    /// Position of the body at `index` one integration step ago.
+    pub fn position_last(&self, index: usize) -> V {
+        return vector(&self.position_last_x, &self.position_last_y, &self.position_last_z, index);
+    }
+
+    /// Sets the previous position, which sets the velocity.
+    pub fn set_position_last(&mut self, index: usize, position_last: V) {
+        set_vector(&mut self.position_last_x, &mut self.position_last_y, &mut self.position_last_z, index, position_last);
+    }
+
+    /// Velocity over the last integration step of length `dt`.
+    pub fn velocity(&self, index: usize, dt: F) -> V {
+        return (self.position(index) - self.position_last(index)) / dt;
+    }
+
+    /// Adds to the acceleration of the body at `index` for the next integration.
+    pub fn accelerate(&mut self, index: usize, acceleration: V) {
+        let acceleration = acceleration.to_vec3();
+
+        self.acceleration_x[index] += acceleration.x;
+        self.acceleration_y[index] += acceleration.y;
+        if V::DIM == 3 {
+            self.acceleration_z[index] += acceleration.z;
+        }
+    }
+
+    /// Verlet step of every body, same as `VerletObject::update`, `update_friction` and `temp_fix` in turn.
+    pub fn integrate(&mut self, dt: F) -> &mut Self {
+        if V::DIM == 3 {
+            return self.integrate_axes::<true>(dt);
+        }
+
+        return self.integrate_axes::<false>(dt);
+    }
+
+    fn integrate_axes<const THREE: bool>(&mut self, dt: F) -> &mut Self {
+        let count = self.len();
+        let dt_squared = dt * dt;
+        let max_temp = F::from_f64(500_000.0);
+        let cooling = F::from_f64(0.00005);
+
+        let position_x = &mut self.position_x[..count];
+        let position_y = &mut self.position_y[..count];
+        let position_last_x = &mut self.position_last_x[..count];
+        let position_last_y = &mut self.position_last_y[..count];
+        let acceleration_x = &mut self.acceleration_x[..count];
+        let acceleration_y = &mut self.acceleration_y[..count];
+        let friction_factor = &self.friction_factor[..count];
+        let temp = &mut self.temp[..count];
+        let fixed = &self.fixed[..count];
+
+        // empty in 2D and never touched there
+        let z_count = if THREE { count } else { 0 };
+        let position_z = &mut self.position_z[..z_count];
+        let position_last_z = &mut self.position_last_z[..z_count];
+        let acceleration_z = &mut self.acceleration_z[..z_count];
+
+        for i in 0..count {
+            // hot fix irrational acceleration
+            if !acceleration_x[i].is_normal() {
+                acceleration_x[i] = F::ZERO;
+            }
+
+            if !acceleration_y[i].is_normal() {
+                acceleration_y[i] = F::ZERO;
+            }
+
+            if THREE && !acceleration_z[i].is_normal() {
+                acceleration_z[i] = F::ZERO;
+            }
+
+            if fixed[i] {
+                position_last_x[i] = position_x[i];
+                position_last_y[i] = position_y[i];
+                if THREE {
+                    position_last_z[i] = position_z[i];
+                }
+            } else {
+                let velocity_x = position_x[i] - position_last_x[i];
+                let velocity_y = position_y[i] - position_last_y[i];
+                position_last_x[i] = position_x[i];
+                position_last_y[i] = position_y[i];
+                position_x[i] += velocity_x + acceleration_x[i] * dt_squared;
+                position_y[i] += velocity_y + acceleration_y[i] * dt_squared;
+
+                if THREE {
+                    let velocity_z = position_z[i] - position_last_z[i];
+                    position_last_z[i] = position_z[i];
+                    position_z[i] += velocity_z + acceleration_z[i] * dt_squared;
+                }
+            }
+
+            acceleration_x[i] = F::ZERO;
+            acceleration_y[i] = F::ZERO;
+            if THREE {
+                acceleration_z[i] = F::ZERO;
+            }
+
+            // friction
+            let velocity_x = position_x[i] - position_last_x[i];
+            let velocity_y = position_y[i] - position_last_y[i];
+            let velocity_z = if THREE { position_z[i] - position_last_z[i] } else { F::ZERO };
+            let speed = (velocity_x * velocity_x + velocity_y * velocity_y + velocity_z * velocity_z).sqrt();
+            position_last_x[i] += velocity_x * (speed * friction_factor[i]);
+            position_last_y[i] += velocity_y * (speed * friction_factor[i]);
+            if THREE {
+                position_last_z[i] += velocity_z * (speed * friction_factor[i]);
+            }
+
+            // cooling
+            if !temp[i].is_normal() {
+                temp[i] = max_temp;
+            }
+
+            temp[i] -= temp[i] * cooling;
+            temp[i] = temp[i].clamp(F::ZERO, max_temp);
+        }
+
+        return self;
+    }
+
+    /// Adds the gravity of every body on every other one, O(N^2) without a grid.
+    /// `source_mass` is each body's mass as a source, zero for bodies that only feel gravity,
+    /// `None` lets every body attract with its own mass. Accelerations are `G m / d^2`
+    /// whatever the mass of the body feeling them, with `d` no less than the larger radius.
+    pub fn accumulate_gravity(&mut self, gravity_const: F, source_mass: Option<&[F]>) -> &mut Self {
+        if V::DIM == 3 {
+            return self.accumulate_gravity_axes::<true>(gravity_const, source_mass);
+        }
+
+        return self.accumulate_gravity_axes::<false>(gravity_const, source_mass);
+    }
+
+    fn accumulate_gravity_axes<const THREE: bool>(&mut self, gravity_const: F, source_mass: Option<&[F]>) -> &mut Self {
+        let count = self.len();
+        let z_count = if THREE { count } else { 0 };
+        let source_mass = source_mass.unwrap_or(&self.mass[..]);
+
+        for i in 0..count {
+            let z = if THREE { self.position_z[i] } else { F::ZERO };
+            let rest = (i + 1).min(z_count);
+
+            // each pair once, the row pushes its partners back by the point's source mass
+            let pull = gravity_row::<F, THREE>(
+                Vec3::new(self.position_x[i], self.position_y[i], z),
+                self.radius[i],
+                gravity_const,
+                gravity_const * source_mass[i],
+                Axes {
+                    x: &self.position_x[i + 1..count],
+                    y: &self.position_y[i + 1..count],
+                    z: &self.position_z[rest..z_count],
+                },
+                &self.radius[i + 1..count],
+                &source_mass[i + 1..count],
+                AxesMut {
+                    x: &mut self.acceleration_x[i + 1..count],
+                    y: &mut self.acceleration_y[i + 1..count],
+                    z: &mut self.acceleration_z[rest..z_count],
+                },
+            );
+
+            self.acceleration_x[i] += pull.x;
+            self.acceleration_y[i] += pull.y;
+            if THREE {
+                self.acceleration_z[i] += pull.z;
+            }
+        }
+
+        return self;
+    }
+}
+
+/// Restores a snapshot taken with `iter`, every body keeps its id, so does `index_of`.
+/// Ids of bodies removed before the snapshot was taken may be handed out again.
+impl<F: Float, V: Vector<F>> FromIterator<VerletObject<F, V>> for Particles<F, V> {
+    fn from_iter<I: IntoIterator<Item = VerletObject<F, V>>>(objects: I) -> Self {
+        let mut particles = Particles::new();
+        for object in objects {
+            particles.push(object);
+        }
+
+        return particles;
+    }
+}
+
+/// Point masses a gravity field is summed over, gathered per axis so the field kernel runs on
+/// contiguous arrays whichever bodies or chunks they came from. `z` stays zero in 2D.
+#[derive(Clone, Default)]
+pub(crate) struct GravitySources<F: Float> {
+    x: Vec<F>,
+    y: Vec<F>,
+    z: Vec<F>,
+    mass: Vec<F>,
+    softening: Vec<F>,
+}
+
+impl<F: Float> GravitySources<F> {
+    /// Empty set with room for `capacity` sources.
+    pub(crate) fn with_capacity(capacity: usize) -> GravitySources<F> {
+        return GravitySources {
+            x: Vec::with_capacity(capacity),
+            y: Vec::with_capacity(capacity),
+            z: Vec::with_capacity(capacity),
+            mass: Vec::with_capacity(capacity),
+            softening: Vec::with_capacity(capacity),
+        };
+    }
+
+    /// Adds a point mass, nothing pulls from closer than `softening`.
+    pub(crate) fn push(&mut self, position: Vec3<F>, mass: F, softening: F) {
+        self.x.push(position.x);
+        self.y.push(position.y);
+        self.z.push(position.z);
+        self.mass.push(mass);
+        self.softening.push(softening);
+    }
+
+    /// Adds the body at `index` with `mass`, softened by its radius, straight from the arrays.
+    pub(crate) fn push_body<V: Vector<F>>(&mut self, particles: &Particles<F, V>, index: usize, mass: F) {
+        self.x.push(particles.position_x[index]);
+        self.y.push(particles.position_y[index]);
+        self.z.push(if V::DIM == 3 { particles.position_z[index] } else { F::ZERO });
+        self.mass.push(mass);
+        self.softening.push(particles.radius[index]);
+    }
+
+    /// The sources from `start` on.
+    pub(crate) fn tail(&self, start: usize) -> SourceSlice<'_, F> {
+        return SourceSlice {
+            x: &self.x[start..],
+            y: &self.y[start..],
+            z: &self.z[start..],
+            mass: &self.mass[start..],
+            softening: &self.softening[start..],
+        };
+    }
+}
+
+/// View of some `GravitySources`, so sources shared by many targets are gathered once.
+#[derive(Copy, Clone)]
+pub(crate) struct SourceSlice<'a, F> {
+    x: &'a [F],
+    y: &'a [F],
+    z: &'a [F],
+    mass: &'a [F],
+    softening: &'a [F],
+}
+
+impl<F: Float, V: Vector<F>> Particles<F, V> {
+    /// Field of all `sources` at each body in `targets`, see `field_at_point`.
+    pub(crate) fn field_at(&self, targets: &[usize], sources: &[SourceSlice<F>]) -> Vec<Vec3<F>> {
+        return targets.iter().map(|index| {
+            let point = vector::<F, V>(&self.position_x, &self.position_y, &self.position_z, *index).to_vec3();
+            return field_at_point::<F>(point, self.radius[*index], V::DIM == 3, sources);
+        }).collect();
+    }
+}
+
+/// Field of all `sources` at `point`, the sum of mass * direction / distance^3, where no
+/// distance counts as less than the larger of the point's and the source's softening.
+/// Sources right at the point add nothing, so a body can be one of its own sources.
+pub(crate) fn field_at_point<F: Float>(point: Vec3<F>, softening: F, three: bool, sources: &[SourceSlice<F>]) -> Vec3<F> {
+    let mut field = Vec3::ZERO;
+    for slice in sources.iter() {
+        if three {
+            field += field_row::<F, true>(point, softening, slice);
+        } else {
+            field += field_row::<F, false>(point, softening, slice);
+        }
+    }
+
+    return field;
+}
+
+#[inline]
+fn field_row<F: Float, const THREE: bool>(point: Vec3<F>, softening: F, sources: &SourceSlice<F>) -> Vec3<F> {
+    let count = sources.mass.len();
+    let vector_count = count - count % LANES;
+    let mut sum_x = [F::ZERO; LANES];
+    let mut sum_y = [F::ZERO; LANES];
+    let mut sum_z = [F::ZERO; LANES];
+
+    for start in (0..vector_count).step_by(LANES) {
+        let end = start + LANES;
+        let chunk_x = &sources.x[start..end];
+        let chunk_y = &sources.y[start..end];
+        let chunk_z = &sources.z[start..end];
+        let chunk_mass = &sources.mass[start..end];
+        let chunk_softening = &sources.softening[start..end];
+
+        for lane in 0..LANES {
+            let direction_x = chunk_x[lane] - point.x;
+            let direction_y = chunk_y[lane] - point.y;
+            let direction_z = if THREE { chunk_z[lane] - point.z } else { F::ZERO };
+            let distance_squared = direction_x * direction_x + direction_y * direction_y + direction_z * direction_z;
+            let strength = chunk_mass[lane] * softened_inverse_cube(distance_squared, softening.max(chunk_softening[lane]));
+
+            sum_x[lane] += direction_x * strength;
+            sum_y[lane] += direction_y * strength;
+            if THREE {
+                sum_z[lane] += direction_z * strength;
+            }
+        }
+    }
+
+    let mut total = Vec3::new(
+        sum_x.iter().copied().sum(),
+        sum_y.iter().copied().sum(),
+        sum_z.iter().copied().sum(),
+    );
+
+    for j in vector_count..count {
+        let direction_x = sources.x[j] - point.x;
+        let direction_y = sources.y[j] - point.y;
+        let direction_z = if THREE { sources.z[j] - point.z } else { F::ZERO };
+        let distance_squared = direction_x * direction_x + direction_y * direction_y + direction_z * direction_z;
+        let strength = sources.mass[j] * softened_inverse_cube(distance_squared, softening.max(sources.softening[j]));
+
+        total += Vec3::new(direction_x, direction_y, direction_z) * strength;
+    }
+
+    return total;
+}
+
+// 1 / d^3 with d no less than `softening`, zero at zero distance, which is how a body skips itself
+#[inline(always)]
+fn softened_inverse_cube<F: Float>(distance_squared: F, softening: F) -> F {
+    if distance_squared > F::ZERO {
+        let distance_squared = distance_squared.max(softening * softening);
+        return F::ONE / (distance_squared * distance_squared.sqrt());
+    }
+
+    return F::ZERO;
+}
+
+fn vector<F: Float, V: Vector<F>>(x: &[F], y: &[F], z: &[F], index: usize) -> V {
+    let z = if V::DIM == 3 { z[index] } else { F::ZERO };
+    return V::from_vec3(Vec3::new(x[index], y[index], z));
+}
+
+fn set_vector<F: Float, V: Vector<F>>(x: &mut [F], y: &mut [F], z: &mut [F], index: usize, value: V) {
+    let value = value.to_vec3();
+
+    x[index] = value.x;
+    y[index] = value.y;
+    if V::DIM == 3 {
+        z[index] = value.z;
+    }
+}
+
+// per axis slices of the bodies a gravity row works on, `z` is empty in 2D
+struct Axes<'a, F> {
+    x: &'a [F],
+    y: &'a [F],
+    z: &'a [F],
+}
+
+struct AxesMut<'a, F> {
+    x: &'a mut [F],
+    y: &'a mut [F],
+    z: &'a mut [F],
+}
+
+// acceleration of a point of `radius` towards the bodies' G * source masses,
+// the bodies get the point's G * source mass `gravity_source` as their push back
+#[inline]
+#[allow(clippy::needless_range_loop, clippy::too_many_arguments)]
+fn gravity_row<F: Float, const THREE: bool>(
+    point: Vec3<F>,
+    radius: F,
+    gravity_const: F,
+    gravity_source: F,
+    position: Axes<F>,
+    radii: &[F],
+    source_mass: &[F],
+    acceleration: AxesMut<F>,
+) -> Vec3<F> {
+    let count = position.x.len();
+    let vector_count = count - count % LANES;
+    let mut sum_x = [F::ZERO; LANES];
+    let mut sum_y = [F::ZERO; LANES];
+    let mut sum_z = [F::ZERO; LANES];
+
+    for start in (0..vector_count).step_by(LANES) {
+        let end = start + LANES;
+        let chunk_x = &position.x[start..end];
+        let chunk_y = &position.y[start..end];
+        let chunk_radius = &radii[start..end];
+        let chunk_source_mass = &source_mass[start..end];
+        let chunk_acceleration_x = &mut acceleration.x[start..end];
+        let chunk_acceleration_y = &mut acceleration.y[start..end];
+
+        let (chunk_z, chunk_acceleration_z) = if THREE {
+            (&position.z[start..end], &mut acceleration.z[start..end])
+        } else {
+            (&position.z[..0], &mut acceleration.z[..0])
+        };
+
+        for lane in 0..LANES {
+            let direction_x = chunk_x[lane] - point.x;
+            let direction_y = chunk_y[lane] - point.y;
+            let direction_z = if THREE { chunk_z[lane] - point.z } else { F::ZERO };
+            let distance_squared = direction_x * direction_x + direction_y * direction_y + direction_z * direction_z;
+            let inverse_cube = softened_inverse_cube(distance_squared, radius.max(chunk_radius[lane]));
+            let strength = gravity_const * chunk_source_mass[lane] * inverse_cube;
+            let reaction = gravity_source * inverse_cube;
+
+            sum_x[lane] += direction_x * strength;
+            sum_y[lane] += direction_y * strength;
+            chunk_acceleration_x[lane] -= direction_x * reaction;
+            chunk_acceleration_y[lane] -= direction_y * reaction;
+
+            if THREE {
+                sum_z[lane] += direction_z * strength;
+                chunk_acceleration_z[lane] -= direction_z * reaction;
+            }
+        }
+    }
+
+    let mut total = Vec3::new(
+        sum_x.iter().copied().sum(),
+        sum_y.iter().copied().sum(),
+        sum_z.iter().copied().sum(),
+    );
+
+    for j in vector_count..count {
+        let direction_x = position.x[j] - point.x;
+        let direction_y = position.y[j] - point.y;
+        let direction_z = if THREE { position.z[j] - point.z } else { F::ZERO };
+        let distance_squared = direction_x * direction_x + direction_y * direction_y + direction_z * direction_z;
+        let inverse_cube = softened_inverse_cube(distance_squared, radius.max(radii[j]));
+
+        total += Vec3::new(direction_x, direction_y, direction_z) * (gravity_const * source_mass[j] * inverse_cube);
+        acceleration.x[j] -= direction_x * (gravity_source * inverse_cube);
+        acceleration.y[j] -= direction_y * (gravity_source * inverse_cube);
+
+        if THREE {
+            acceleration.z[j] -= direction_z * (gravity_source * inverse_cube);
+        }
+    }
+
+    return total;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn body(x: f64) -> VerletObject {
+        return VerletObject::with_velocity(Vec2::new(x, 0.0), Vec2::ZERO, 1.0, 1.0, 0.001);
+    }
+
+    #[test]
+    fn integrate_matches_verlet_object() {
+        let mut rnd = StdRng::seed_from_u64(7);
+        let mut objects: Vec<VerletObject<f64, Vec3>> = Vec::new();
+        for index in 0..37 {
+            let position = Vec3::new(rnd.gen_range(-9.0..9.0), rnd.gen_range(-9.0..9.0), rnd.gen_range(-9.0..9.0));
+            let velocity = Vec3::new(rnd.gen_range(-3.0..3.0), rnd.gen_range(-3.0..3.0), rnd.gen_range(-3.0..3.0));
+            let mut object = VerletObject::with_velocity(position, velocity, 1.0, 1.0, 0.01);
+            object.acceleration = Vec3::new(rnd.gen_range(-50.0..50.0), rnd.gen_range(-50.0..50.0), rnd.gen_range(-50.0..50.0));
+            object.friction_factor = rnd.gen_range(0.0..0.01);
+            object.temp = rnd.gen_range(0.0..600_000.0);
+            object.fixed = index % 5 == 0;
+            objects.push(object);
+        }
+
+        let mut particles: Particles<f64, Vec3> = Particles::new();
+        for object in objects.iter() {
+            particles.push(*object);
+        }
+
+        for _step in 0..3 {
+            particles.integrate(0.01);
+            for object in objects.iter_mut() {
+                object.update(0.01);
+                object.update_friction();
+                object.temp_fix();
+            }
+        }
+
+        for (index, object) in objects.iter().enumerate() {
+            let soa = particles.get(index);
+            assert!((soa.position - object.position).length() < 1e-12);
+            assert!((soa.position_last - object.position_last).length() < 1e-12);
+            assert!((soa.temp - object.temp).abs() < 1e-6);
+            assert_eq!(soa.acceleration, Vec3::ZERO);
+        }
+    }
+
+    #[test]
+    fn index_of_follows_swap_remove() {
+        let mut particles: Particles = Particles::new();
+        particles.push(body(0.0)).push(body(1.0)).push(body(2.0));
+        let (first, middle, last) = (particles.id(0), particles.id(1), particles.id(2));
+
+        let removed = particles.swap_remove(0);
+        assert_eq!(removed.id, first);
+        assert_eq!(particles.index_of(first), None);
+        assert_eq!(particles.index_of(last), Some(0));
+        assert_eq!(particles.index_of(middle), Some(1));
+        assert_eq!(particles.position(0), Vec2::new(2.0, 0.0));
+
+        // removing the last body moves nothing
+        particles.swap_remove(1);
+        assert_eq!(particles.index_of(middle), None);
+        assert_eq!(particles.index_of(last), Some(0));
+
+        // a copy pushed back gets its id again
+        particles.push(removed);
+        assert_eq!(particles.index_of(first), Some(1));
+    }
+
+    #[test]
+    fn metadata_travels_with_the_body() {
+        let mut particles: Particles = Particles::new();
+        for index in 0..4 {
+            let mut object = body(index as f64);
+            object.species = index as u32 + 10;
+            object.user_data = index as u64 * 100;
+            particles.push(object);
+        }
+
+        // the last body moves into the removed one's place and takes its metadata along
+        let removed = particles.swap_remove(1);
+        assert_eq!((removed.species, removed.user_data), (11, 100));
+        assert_eq!((particles.species[1], particles.user_data[1]), (13, 300));
+
+        let mut object = particles.get(1);
+        assert_eq!((object.species, object.user_data), (13, 300));
+        object.species = 7;
+        object.user_data = 70;
+        particles.set(0, &object);
+        assert_eq!((particles.get(0).species, particles.get(0).user_data), (7, 70));
+        assert_ne!(particles.id(0), particles.id(1));
+
+        let copy = particles.clone();
+        for index in 0..particles.len() {
+            assert_eq!(copy.id(index), particles.id(index));
+            assert_eq!(copy.species[index], particles.species[index]);
+            assert_eq!(copy.user_data[index], particles.user_data[index]);
+        }
+    }
+
+    #[test]
+    fn snapshot_restores_ids_and_metadata() {
+        let mut particles: Particles = Particles::new();
+        for index in 0..5 {
+            let mut object = body(index as f64);
+            object.species = index as u32;
+            object.user_data = index as u64 + 1000;
+            particles.push(object);
+        }
+        particles.swap_remove(0);
+
+        let snapshot: Vec<VerletObject> = particles.iter().collect();
+        let mut restored: Particles = snapshot.into_iter().collect();
+        for index in 0..particles.len() {
+            let id = particles.id(index);
+            assert_eq!(restored.index_of(id), Some(index));
+            assert_eq!(restored.position(index), particles.position(index));
+            assert_eq!(restored.species[index], particles.species[index]);
+            assert_eq!(restored.user_data[index], particles.user_data[index]);
+        }
+
+        // bodies added after the restore get ids of their own
+        restored.push(body(9.0));
+        let new_id = restored.id(restored.len() - 1);
+        assert!((0..particles.len()).all(|index| particles.id(index) != new_id));
+    }
+}
@@ -0,0 +1,264 @@
+use super::vec3::Vec3;
+
+/// Fixed analytic field added to the bodies' own gravity, see `VerletWorld::potentials`.
+///
+/// Parameters are in world units with the world's `gravity_const`, everything but `PointMass`
+/// sits at the origin. Like body to body gravity these give true accelerations, the same
+/// for every body whatever its mass, so orbits match the textbook circular velocities.
+#[derive(Copy, Clone, Debug)]
+pub enum Potential {
+    /// Kepler field `-G M / sqrt(r^2 + softening^2)`, e.g. a central black hole.
+    PointMass {
+        /// Position of the mass.
+        center: Vec3,
+        /// Mass M.
+        mass: f64,
+        /// Plummer softening length, zero for the bare Kepler field.
+        softening: f64,
+    },
+    /// `v0^2 / 2 * ln(core_radius^2 + x^2 + y^2 / q_y^2 + z^2 / q_z^2)`, flat rotation curve
+    /// of `velocity` outside the core (Binney & Tremaine 2008, eq. 2.71).
+    Logarithmic {
+        /// Circular velocity v0 far outside the core.
+        velocity: f64,
+        /// Radius inside which the rotation curve rises linearly.
+        core_radius: f64,
+        /// Axis ratio q_y, one for a round potential.
+        flattening_y: f64,
+        /// Axis ratio q_z, one for a round potential.
+        flattening_z: f64,
+    },
+    /// Navarro, Frenk & White (1996) halo `-G M ln(1 + r / r_s) / r`,
+    /// where `mass` is `4 pi rho_0 r_s^3` rather than a virial mass.
+    Nfw {
+        /// Mass scale `4 pi rho_0 r_s^3`.
+        mass: f64,
+        /// Scale radius r_s, where the density slope turns from -1 to -3.
+        scale_radius: f64,
+    },
+    /// Miyamoto & Nagai (1975) disk in the xy plane `-G M / sqrt(R^2 + (a + sqrt(z^2 + b^2))^2)`,
+    /// `scale_height` b going to zero gives a Kuzmin disk and `scale_length` a a Plummer sphere.
+    MiyamotoNagai {
+        /// Total mass M.
+        mass: f64,
+        /// Radial scale a.
+        scale_length: f64,
+        /// Vertical scale b.
+        scale_height: f64,
+    },
+    /// Spring towards the origin `(w_x^2 x^2 + w_y^2 y^2 + w_z^2 z^2) / 2`, `frequency` per axis.
+    Harmonic {
+        /// Angular frequencies w_x, w_y and w_z.
+        frequency: Vec3,
+    },
+    /// Long & Murali (1992) softened needle of `mass` and half length `half_length` along its
+    /// x axis, turning about z at `pattern_speed` from `angle` at time zero.
+    /// `scale_length` and `scale_height` soften it in the plane and out of it.
+    RotatingBar {
+        /// Total mass of the needle.
+        mass: f64,
+        /// Half the needle's length.
+        half_length: f64,
+        /// Softening in the plane.
+        scale_length: f64,
+        /// Softening out of the plane.
+        scale_height: f64,
+        /// Angular velocity about z, positive counterclockwise.
+        pattern_speed: f64,
+        /// Angle of the needle against the x axis at time zero.
+        angle: f64,
+    },
+}
+
+impl Potential {
+    /// Acceleration at `position` and `time`.
+    pub fn acceleration(&self, position: Vec3, time: f64, gravity_const: f64) -> Vec3 {
+        match *self {
+            Potential::PointMass { center, mass, softening } => {
+                let offset = position - center;
+                let distance_squared = offset.length_square() + softening * softening;
+                if distance_squared == 0.0 {
+                    return Vec3::ZERO;
+                }
+
+                return -offset * (gravity_const * mass / (distance_squared * distance_squared.sqrt()));
+            }
+            Potential::Logarithmic { velocity, core_radius, flattening_y, flattening_z } => {
+                let q_y = flattening_y * flattening_y;
+                let q_z = flattening_z * flattening_z;
+                let scale = velocity * velocity / logarithmic_denominator(position, core_radius, q_y, q_z);
+
+                return -Vec3::new(position.x, position.y / q_y, position.z / q_z) * scale;
+            }
+            Potential::Nfw { mass, scale_radius } => {
+                let r = position.length();
+                if r == 0.0 {
+                    return Vec3::ZERO;
+                }
+
+                // dphi/dr over r
+                let slope = gravity_const * mass * ((r / scale_radius).ln_1p() / r - 1.0 / (scale_radius + r)) / (r * r);
+                return -position * slope;
+            }
+            Potential::MiyamotoNagai { mass, scale_length, scale_height } => {
+                let zeta = (position.z * position.z + scale_height * scale_height).sqrt();
+                let height = scale_length + zeta;
+                let distance_squared = position.x * position.x + position.y * position.y + height * height;
+                let scale = gravity_const * mass / (distance_squared * distance_squared.sqrt());
+                let z = if zeta > 0.0 { position.z * height / zeta } else { 0.0 };
+
+                return -Vec3::new(position.x, position.y, z) * scale;
+            }
+            Potential::Harmonic { frequency } => {
+                return -Vec3::new(
+                    frequency.x * frequency.x * position.x,
+                    frequency.y * frequency.y * position.y,
+                    frequency.z * frequency.z * position.z,
+                );
+            }
+            Potential::RotatingBar { mass, half_length, scale_length, scale_height, pattern_speed, angle } => {
+                // evaluated in the bar's frame and turned back
+                let angle = angle + pattern_speed * time;
+                let point = position.rotate_z(-angle);
+                let bar = bar_terms(point, half_length, scale_length, scale_height);
+                let k = gravity_const * mass / (2.0 * half_length);
+                let cross = 1.0 / (bar.minus * (point.x - half_length + bar.minus)) - 1.0 / (bar.plus * (point.x + half_length + bar.plus));
+                let z = if bar.zeta > 0.0 { bar.softening * point.z / bar.zeta } else { 0.0 };
+                let gradient = Vec3::new(1.0 / bar.minus - 1.0 / bar.plus, point.y * cross, z * cross) * k;
+
+                return -gradient.rotate_z(angle);
+            }
+        }
+    }
+
+    /// Potential energy per unit mass at `position` and `time`.
+    pub fn energy(&self, position: Vec3, time: f64, gravity_const: f64) -> f64 {
+        match *self {
+            Potential::PointMass { center, mass, softening } => {
+                let distance_squared = (position - center).length_square() + softening * softening;
+                if distance_squared == 0.0 {
+                    return f64::NEG_INFINITY;
+                }
+
+                return -gravity_const * mass / distance_squared.sqrt();
+            }
+            Potential::Logarithmic { velocity, core_radius, flattening_y, flattening_z } => {
+                let denominator = logarithmic_denominator(position, core_radius, flattening_y * flattening_y, flattening_z * flattening_z);
+                return 0.5 * velocity * velocity * denominator.ln();
+            }
+            Potential::Nfw { mass, scale_radius } => {
+                let r = position.length();
+                if r == 0.0 {
+                    return -gravity_const * mass / scale_radius;
+                }
+
+                return -gravity_const * mass * (r / scale_radius).ln_1p() / r;
+            }
+            Potential::MiyamotoNagai { mass, scale_length, scale_height } => {
+                let height = scale_length + (position.z * position.z + scale_height * scale_height).sqrt();
+                return -gravity_const * mass / (position.x * position.x + position.y * position.y + height * height).sqrt();
+            }
+            Potential::Harmonic { frequency } => {
+                return 0.5 * (
+                    frequency.x * frequency.x * position.x * position.x
+                    + frequency.y * frequency.y * position.y * position.y
+                    + frequency.z * frequency.z * position.z * position.z
+                );
+            }
+            Potential::RotatingBar { mass, half_length, scale_length, scale_height, pattern_speed, angle } => {
+                let point = position.rotate_z(-(angle + pattern_speed * time));
+                let bar = bar_terms(point, half_length, scale_length, scale_height);
+                let k = gravity_const * mass / (2.0 * half_length);
+
+                return k * ((point.x - half_length + bar.minus) / (point.x + half_length + bar.plus)).ln();
+            }
+        }
+    }
+
+    /// Speed of a circular orbit of `radius` on the x axis, from the inward pull alone.
+    /// Handy for setting up disks inside halos, zero where the field pushes outwards.
+    pub fn circular_velocity(&self, radius: f64, time: f64, gravity_const: f64) -> f64 {
+        let pull = -self.acceleration(Vec3::new(radius, 0.0, 0.0), time, gravity_const).x;
+        return (radius * pull).max(0.0).sqrt();
+    }
+}
+
+fn logarithmic_denominator(position: Vec3, core_radius: f64, q_y: f64, q_z: f64) -> f64 {
+    return core_radius * core_radius + position.x * position.x + position.y * position.y / q_y + position.z * position.z / q_z;
+}
+
+// distances to the softened needle's ends, shared by the field and the energy
+struct BarTerms {
+    minus: f64,
+    plus: f64,
+    softening: f64,
+    zeta: f64,
+}
+
+fn bar_terms(point: Vec3, half_length: f64, scale_length: f64, scale_height: f64) -> BarTerms {
+    let zeta = (scale_height * scale_height + point.z * point.z).sqrt();
+    let softening = scale_length + zeta;
+    let rest = point.y * point.y + softening * softening;
+
+    return BarTerms {
+        minus: ((half_length - point.x) * (half_length - point.x) + rest).sqrt(),
+        plus: ((half_length + point.x) * (half_length + point.x) + rest).sqrt(),
+        softening,
+        zeta,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G: f64 = 6.674;
+
+    fn assert_circular_velocity(potential: Potential, radius: f64, expected: f64) {
+        let velocity = potential.circular_velocity(radius, 0.0, G);
+        assert!((velocity - expected).abs() < 1e-12 * expected, "{potential:?} at {radius}: {velocity} against {expected}");
+    }
+
+    #[test]
+    fn circular_velocities_match_analytic_curves() {
+        for r in [0.5, 3.0, 40.0] {
+            assert_circular_velocity(
+                Potential::PointMass { center: Vec3::ZERO, mass: 1000.0, softening: 0.0 },
+                r,
+                f64::sqrt(G * 1000.0 / r),
+            );
+            assert_circular_velocity(
+                Potential::PointMass { center: Vec3::ZERO, mass: 1000.0, softening: 2.0 },
+                r,
+                f64::sqrt(G * 1000.0 * r * r / f64::powf(r * r + 4.0, 1.5)),
+            );
+            assert_circular_velocity(
+                Potential::Logarithmic { velocity: 20.0, core_radius: 5.0, flattening_y: 1.0, flattening_z: 0.8 },
+                r,
+                20.0 * r / f64::sqrt(25.0 + r * r),
+            );
+
+            let x = r / 10.0;
+            assert_circular_velocity(
+                Potential::Nfw { mass: 1000.0, scale_radius: 10.0 },
+                r,
+                f64::sqrt(G * 1000.0 * (f64::ln(1.0 + x) - x / (1.0 + x)) / r),
+            );
+            assert_circular_velocity(
+                Potential::MiyamotoNagai { mass: 1000.0, scale_length: 3.0, scale_height: 0.5 },
+                r,
+                f64::sqrt(G * 1000.0 * r * r / f64::powf(r * r + 3.5 * 3.5, 1.5)),
+            );
+            assert_circular_velocity(Potential::Harmonic { frequency: Vec3::new(0.2, 0.3, 0.4) }, r, 0.2 * r);
+        }
+
+        // an unsoftened needle pulls G M / (x^2 - L^2) along its axis beyond its ends
+        for x in [4.0, 10.0, 40.0] {
+            assert_circular_velocity(
+                Potential::RotatingBar { mass: 1000.0, half_length: 2.0, scale_length: 0.0, scale_height: 0.0, pattern_speed: 0.1, angle: 0.0 },
+                x,
+                f64::sqrt(G * 1000.0 * x / (x * x - 4.0)),
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::metrics::StepMetrics;
+
+const BUCKETS_PER_OCTAVE: usize = 4;
+// 2^40 ns is about 18 minutes, anything slower lands in the last bucket
+const OCTAVES: usize = 40;
+
+/// Timed part of a step, see `StepMetrics`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// See `StepMetrics::grid`.
+    Grid,
+    /// See `StepMetrics::collisions`.
+    Collisions,
+    /// See `StepMetrics::gravity`.
+    Gravity,
+    /// See `StepMetrics::tracers`.
+    Tracers,
+    /// See `StepMetrics::integration`.
+    Integration,
+    /// See `StepMetrics::constraints`.
+    Constraints,
+    /// See `StepMetrics::rendering`.
+    Rendering,
+    /// See `StepMetrics::frame`.
+    Frame,
+}
+
+impl Phase {
+    /// Every phase, in `StepMetrics` column order.
+    pub const ALL: [Phase; 8] = [
+        Phase::Grid,
+        Phase::Collisions,
+        Phase::Gravity,
+        Phase::Tracers,
+        Phase::Integration,
+        Phase::Constraints,
+        Phase::Rendering,
+        Phase::Frame,
+    ];
+
+    /// Lowercase name, as in the CSV columns.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Grid => "grid",
+            Phase::Collisions => "collisions",
+            Phase::Gravity => "gravity",
+            Phase::Tracers => "tracers",
+            Phase::Integration => "integration",
+            Phase::Constraints => "constraints",
+            Phase::Rendering => "rendering",
+            Phase::Frame => "frame",
+        }
+    }
+}
+
+/// Log-scale histogram of durations, four buckets per doubling from 1ns up.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    /// Sample counts per bucket, see `bucket_bounds`.
+    pub buckets: Vec<u64>,
+    /// Number of samples.
+    pub count: u64,
+    /// Sum of all samples.
+    pub total: Duration,
+    /// Shortest sample, `Duration::MAX` while empty.
+    pub min: Duration,
+    /// Longest sample.
+    pub max: Duration,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        return Histogram::new();
+    }
+}
+
+impl Histogram {
+    /// Empty histogram.
+    pub fn new() -> Histogram {
+        Histogram {
+            buckets: vec![0; BUCKETS_PER_OCTAVE * OCTAVES],
+            count: 0,
+            total: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        }
+    }
+
+    /// Adds one sample.
+    pub fn record(&mut self, duration: Duration) {
+        self.buckets[bucket_index(duration)] += 1;
+        self.count += 1;
+        self.total += duration;
+        self.min = self.min.min(duration);
+        self.max = self.max.max(duration);
+    }
+
+    /// Mean of the samples, zero while empty.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        return self.total.div_f64(self.count as f64);
+    }
+
+    /// Upper edge of the bucket holding the given fraction of samples, clamped to the observed range.
+    pub fn percentile(&self, fraction: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+
+        let target = (fraction.clamp(0.0, 1.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= target {
+                return bucket_bounds(index).1.clamp(self.min, self.max);
+            }
+        }
+
+        return self.max;
+    }
+}
+
+/// Lower and upper edge of a histogram bucket.
+pub fn bucket_bounds(index: usize) -> (Duration, Duration) {
+    let edge = |i: usize| Duration::from_nanos(f64::powf(2.0, i as f64 / BUCKETS_PER_OCTAVE as f64) as u64);
+    return (edge(index), edge(index + 1));
+}
+
+fn bucket_index(duration: Duration) -> usize {
+    let nanos = duration.as_nanos().max(1) as f64;
+    let index = (nanos.log2() * BUCKETS_PER_OCTAVE as f64) as usize;
+
+    return index.min(BUCKETS_PER_OCTAVE * OCTAVES - 1);
+}
+
+/// Per-phase histograms over every recorded step.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    /// Indexed by `Phase as usize`.
+    pub histograms: [Histogram; Phase::ALL.len()],
+}
+
+impl Profile {
+    /// Empty profile.
+    pub fn new() -> Profile {
+        return Profile::default();
+    }
+
+    /// Adds every phase of one step.
+    pub fn record(&mut self, metrics: &StepMetrics) {
+        for phase in Phase::ALL {
+            self.histograms[phase as usize].record(metrics.phase(phase));
+        }
+    }
+
+    /// Histogram of one phase.
+    pub fn histogram(&self, phase: Phase) -> &Histogram {
+        return &self.histograms[phase as usize];
+    }
+
+    /// Number of recorded steps.
+    pub fn steps(&self) -> u64 {
+        return self.histogram(Phase::Frame).count;
+    }
+}
+
+// one row per phase, fit for a log line or a terminal
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<12} {:>12} {:>12} {:>12} {:>12} {:>12}", "phase", "mean", "p50", "p90", "p99", "max")?;
+
+        for phase in Phase::ALL {
+            let histogram = self.histogram(phase);
+            writeln!(
+                f,
+                "{:<12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+                phase.name(),
+                format!("{:.1?}", histogram.mean()),
+                format!("{:.1?}", histogram.percentile(0.5)),
+                format!("{:.1?}", histogram.percentile(0.9)),
+                format!("{:.1?}", histogram.percentile(0.99)),
+                format!("{:.1?}", if histogram.count > 0 { histogram.max } else { Duration::ZERO }),
+            )?;
+        }
+
+        return Ok(());
+    }
+}
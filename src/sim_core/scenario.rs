@@ -0,0 +1,108 @@
+//! Initial-condition generators that populate a `VerletWorld`.
+
+use std::f64::consts::PI;
+use rand::Rng;
+use rayon::prelude::*;
+
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
+use super::verlet_object::VerletObject;
+use super::verlet_world::VerletWorld;
+
+pub mod plummer;
+pub mod disk;
+pub mod composite;
+pub mod kepler;
+pub mod few_body;
+pub mod cold_collapse;
+pub mod protoplanetary;
+
+/// Something that can add its bodies to a world, velocities use the world's `gravity_const`.
+///
+/// Generators build their systems in 3D, 2D worlds get them projected onto the xy plane.
+pub trait Scenario<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Adds the bodies, and any emitters, to `world`.
+    fn spawn(&self, world: &mut VerletWorld<F, V>);
+}
+
+// generators work in f64 whatever precision the world runs in,
+// bodies get no friction so the equilibria they are set up in hold
+fn spawn_body<F: Float, V: Vector<F>>(world: &mut VerletWorld<F, V>, position: Vec3, velocity: Vec3, mass: f64, radius: f64) {
+    let dt = world.sub_dt();
+    let mut object = VerletObject::with_velocity(V::from_vec3(position.cast()), V::from_vec3(velocity.cast()), F::from_f64(mass), F::from_f64(radius), dt);
+    object.friction_factor = F::ZERO;
+    world.objects.push(object);
+}
+
+// what a world of `V` keeps of a vector, the plane drops z
+fn project<F: Float, V: Vector<F>>(v: Vec3) -> Vec3 {
+    if V::DIM == 2 {
+        return Vec3::new(v.x, v.y, 0.0);
+    }
+
+    return v;
+}
+
+// isotropic unit vector
+fn random_direction<R: Rng>(rnd: &mut R) -> Vec3 {
+    let z: f64 = rnd.gen_range(-1.0..1.0);
+    let phi: f64 = rnd.gen_range(0.0..2.0 * PI);
+    let planar = f64::sqrt(1.0 - z * z);
+
+    return Vec3::new(planar * f64::cos(phi), planar * f64::sin(phi), z);
+}
+
+// shifts positions and velocities so the centre of mass rests at the origin
+fn to_center_of_mass_frame(positions: &mut [Vec3], velocities: &mut [Vec3], masses: &[f64]) {
+    let mut mass = 0.0;
+    let mut position = Vec3::ZERO;
+    let mut velocity = Vec3::ZERO;
+
+    for i in 0..masses.len() {
+        mass += masses[i];
+        position += positions[i] * masses[i];
+        velocity += velocities[i] * masses[i];
+    }
+
+    if mass <= 0.0 {
+        return;
+    }
+
+    let position = position / mass;
+    let velocity = velocity / mass;
+
+    for i in 0..masses.len() {
+        positions[i] -= position;
+        velocities[i] -= velocity;
+    }
+}
+
+fn kinetic_energy(velocities: &[Vec3], masses: &[f64]) -> f64 {
+    let mut energy = 0.0;
+
+    for i in 0..masses.len() {
+        energy += 0.5 * masses[i] * velocities[i].length_square();
+    }
+
+    return energy;
+}
+
+fn potential_energy(positions: &[Vec3], masses: &[f64], gravity_const: f64) -> f64 {
+    return (0..positions.len())
+        .into_par_iter()
+        .map(|i| {
+            let mut energy = 0.0;
+
+            for j in (i + 1)..positions.len() {
+                let distance = (positions[i] - positions[j]).length();
+                if distance > 0.0 {
+                    energy -= gravity_const * masses[i] * masses[j] / distance;
+                }
+            }
+
+            energy
+        })
+        .sum();
+}
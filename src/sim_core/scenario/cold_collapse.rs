@@ -0,0 +1,122 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+use rand::Rng;
+
+use super::{Scenario, spawn_body, project, random_direction};
+use super::super::emitter::sample;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+#[derive(Copy, Clone)]
+/// Initial distribution of a `ColdCollapse`.
+pub enum Shape {
+    /// Uniform surface density.
+    Disk,
+    /// Uniform density sphere, projected onto the plane in 2D worlds.
+    Sphere,
+}
+
+/// Uniform system released from rest, or with some solid-body rotation, to study violent relaxation.
+pub struct ColdCollapse {
+    /// Disk or sphere.
+    pub shape: Shape,
+    /// Total mass, shared equally by the bodies.
+    pub mass: f64,
+    /// Radius of the disk or sphere.
+    pub size: f64,
+    /// Number of bodies.
+    pub count: i32,
+    /// Body radii are drawn uniformly from this range.
+    pub radius_range: Range<f64>,
+    /// Angular speed around the z axis in units of `sqrt(G M / size^3)`, zero is a truly cold start.
+    pub rotation: f64,
+}
+
+impl ColdCollapse {
+    /// Non-rotating collapse, see `rotation`.
+    pub fn new(shape: Shape, mass: f64, size: f64, count: i32, radius_range: Range<f64>) -> ColdCollapse {
+        ColdCollapse {
+            shape,
+            mass,
+            size,
+            count,
+            radius_range,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for ColdCollapse {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let gravity_const = world.gravity_const.to_f64();
+        let mut rnd = rand::thread_rng();
+        let count = self.count.max(0);
+        let body_mass = self.mass / count as f64;
+        let angular_speed = self.rotation * f64::sqrt(gravity_const * self.mass / f64::powi(self.size, 3));
+
+        for _step in 0..count {
+            let position = match self.shape {
+                Shape::Disk => {
+                    let r = self.size * f64::sqrt(rnd.gen_range(0.0..1.0));
+                    let angle: f64 = rnd.gen_range(0.0..2.0 * PI);
+
+                    Vec3::new(r * f64::cos(angle), r * f64::sin(angle), 0.0)
+                }
+                Shape::Sphere => {
+                    let r = self.size * f64::cbrt(rnd.gen_range(0.0..1.0));
+                    project::<F, V>(random_direction(&mut rnd) * r)
+                }
+            };
+
+            spawn_body(
+                world,
+                position,
+                Vec3::new(-position.y, position.x, 0.0) * angular_speed,
+                body_mass,
+                sample(&mut rnd, self.radius_range.clone()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::vec2::Vec2;
+
+    #[test]
+    fn starts_at_rest() {
+        for shape in [Shape::Disk, Shape::Sphere] {
+            // a single radius is a valid range
+            let collapse = ColdCollapse::new(shape, 1000.0, 100.0, 100, 1.0..1.0);
+            let mut world = VerletWorld::<f64, Vec3>::new(0, 540.0);
+            collapse.spawn(&mut world);
+
+            let dt = world.sub_dt();
+            assert_eq!(world.objects.len(), 100);
+            for index in 0..world.objects.len() {
+                assert_eq!(world.objects.velocity(index, dt), Vec3::ZERO);
+                assert!(world.objects.position(index).length() <= collapse.size);
+                assert_eq!(world.objects.radius[index], 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn rotates_as_a_solid_body() {
+        let mut collapse = ColdCollapse::new(Shape::Disk, 1000.0, 100.0, 100, 0.5..1.0);
+        collapse.rotation = 0.5;
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        collapse.spawn(&mut world);
+
+        let dt = world.sub_dt();
+        let angular_speed = 0.5 * f64::sqrt(world.gravity_const * collapse.mass / f64::powi(collapse.size, 3));
+        for index in 0..world.objects.len() {
+            let position = world.objects.position(index);
+            let expected = Vec2::new(-position.y, position.x) * angular_speed;
+            assert!((world.objects.velocity(index, dt) - expected).length() < 1e-9);
+        }
+    }
+}
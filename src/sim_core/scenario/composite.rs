@@ -0,0 +1,160 @@
+use super::Scenario;
+use super::super::sink::Region;
+use super::super::vec2::Vec2;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+/// Sub-system of a `Composite`, placed at `offset` and moving with `velocity`.
+pub struct Component<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// The sub-system itself, spawned around the origin at rest.
+    pub scenario: Box<dyn Scenario<F, V>>,
+    /// Where the sub-system's origin ends up.
+    pub offset: Vec3,
+    /// Bulk velocity added to every body of the sub-system.
+    pub velocity: Vec3,
+    /// Radians, counter-clockwise around the z axis through the sub-system origin.
+    pub rotation: f64,
+    /// Radians, tilt around the x axis applied before `rotation`, 2D worlds see it foreshortened.
+    pub inclination: f64,
+    /// Mirrors the sub-system before rotating it, flipping its spin.
+    pub retrograde: bool,
+    /// Tags the sub-system's bodies and emitters, `None` keeps what the scenario set.
+    pub species: Option<u32>,
+}
+
+/// Several sub-systems merged into one world, e.g. two galaxies set up for a merger or a flyby.
+pub struct Composite<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Sub-systems in spawn order.
+    pub components: Vec<Component<F, V>>,
+}
+
+impl<F: Float, V: Vector<F>> Default for Composite<F, V> {
+    fn default() -> Self {
+        return Composite::new();
+    }
+}
+
+impl<F: Float, V: Vector<F>> Composite<F, V> {
+    /// Composite without components.
+    pub fn new() -> Composite<F, V> {
+        Composite {
+            components: Vec::new(),
+        }
+    }
+
+    /// Adds a sub-system, the returned component sets the rest of its placement.
+    pub fn add<S: Scenario<F, V> + 'static>(&mut self, scenario: S, offset: Vec3, velocity: Vec3, rotation: f64) -> &mut Component<F, V> {
+        self.components.push(Component {
+            scenario: Box::new(scenario),
+            offset,
+            velocity,
+            rotation,
+            inclination: 0.0,
+            retrograde: false,
+            species: None,
+        });
+
+        return self.components.last_mut().unwrap();
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for Composite<F, V> {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let dt = world.sub_dt().to_f64();
+        let tracer_dt = world.step_dt().to_f64();
+
+        for component in self.components.iter() {
+            let first_index = world.objects.len();
+            let first_tracer = world.tracers.len();
+            let first_emitter = world.emitters.len();
+            let first_sink = world.sinks.len();
+            component.scenario.spawn(world);
+
+            let drift = V::from_vec3((component.velocity * dt).cast());
+            let tracer_drift = V::from_vec3((component.velocity * tracer_dt).cast());
+            let transform = |point: V| -> V {
+                let point: Vec3 = point.to_vec3().cast();
+                let y = if component.retrograde { -point.y } else { point.y };
+                let placed = Vec3::new(point.x, y, point.z)
+                    .rotate_x(component.inclination)
+                    .rotate_z(component.rotation)
+                    + component.offset;
+
+                return V::from_vec3(placed.cast());
+            };
+
+            for index in first_index..world.objects.len() {
+                world.objects.modify(index, |object| {
+                    object.position = transform(object.position);
+                    object.position_last = transform(object.position_last) - drift;
+                    if let Some(species) = component.species {
+                        object.species = species;
+                    }
+                });
+            }
+
+            // tracers step once per update, so their velocity is a displacement per update
+            for index in first_tracer..world.tracers.len() {
+                world.tracers.position[index] = transform(world.tracers.position[index]);
+                world.tracers.position_last[index] = transform(world.tracers.position_last[index]) - tracer_drift;
+            }
+
+            // emitters move along, their extent stays axis aligned
+            let origin = transform(V::ZERO);
+            let velocity = V::from_vec3(component.velocity.cast());
+            for emitter in world.emitters[first_emitter..].iter_mut() {
+                emitter.position = transform(emitter.position);
+                emitter.velocity = transform(emitter.velocity) - origin + velocity;
+                if let Some(species) = component.species {
+                    emitter.species = species;
+                }
+            }
+            for sink in world.sinks[first_sink..].iter_mut() {
+                match &mut sink.region {
+                    Region::Beyond { center, .. } | Region::Within { center, .. } => *center = transform(*center),
+                    Region::AroundBody { .. } => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    // one body and one tracer at (1, 0) moving along +y
+    struct Pair;
+
+    impl Scenario<f64, Vec2> for Pair {
+        fn spawn(&self, world: &mut VerletWorld<f64, Vec2>) {
+            world.spawn(Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0), 1.0, 1.0);
+            let dt = world.step_dt();
+            world.tracers.push(Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0), dt);
+        }
+    }
+
+    #[test]
+    fn places_bodies_and_tracers() {
+        let mut composite = Composite::<f64, Vec2>::new();
+        composite.add(Pair, Vec3::new(100.0, 50.0, 0.0), Vec3::new(3.0, -2.0, 0.0), 0.5 * PI);
+        composite.add(Pair, Vec3::new(-100.0, 0.0, 0.0), Vec3::ZERO, 0.0).retrograde = true;
+
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        composite.spawn(&mut world);
+
+        // turned a quarter and moved, then mirrored in place
+        let expected = [(Vec2::new(100.0, 51.0), Vec2::new(2.0, -2.0)), (Vec2::new(-99.0, 0.0), Vec2::new(0.0, -1.0))];
+        let (sub_dt, step_dt) = (world.sub_dt(), world.step_dt());
+        for (index, (position, velocity)) in expected.into_iter().enumerate() {
+            assert!((world.objects.position(index) - position).length() < 1e-9);
+            assert!((world.objects.velocity(index, sub_dt) - velocity).length() < 1e-9);
+            assert!((world.tracers.position[index] - position).length() < 1e-9);
+            assert!((world.tracers.velocity(index, step_dt) - velocity).length() < 1e-9);
+        }
+    }
+}
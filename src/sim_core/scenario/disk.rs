@@ -0,0 +1,188 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+use rand::Rng;
+
+use super::{Scenario, spawn_body};
+use super::plummer::Plummer;
+use super::super::emitter::sample;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+/// Rotating disk galaxy with exponential surface density, optionally embedded in a bulge and a halo.
+pub struct ExponentialDisk {
+    /// Disk mass, shared equally by the bodies.
+    pub mass: f64,
+    /// Exponential scale length of the surface density.
+    pub scale_length: f64,
+    /// Number of disk bodies.
+    pub count: i32,
+    /// Body radii are drawn uniformly from this range.
+    pub radius_range: Range<f64>,
+    /// Bodies further than `truncation * scale_length` are resampled.
+    pub truncation: f64,
+    /// Thickness of an isothermal sech^2 layer in 3D worlds, zero keeps the disk flat.
+    pub scale_height: f64,
+
+    /// Single central body, skipped when `bulge_mass` is zero.
+    pub bulge_mass: f64,
+    /// Radius of the central body.
+    pub bulge_radius: f64,
+
+    /// Live halo spawned with the disk, its mass counts towards the rotation curve.
+    pub halo: Option<Plummer>,
+}
+
+impl ExponentialDisk {
+    /// Flat disk truncated at five scale lengths, without bulge or halo.
+    pub fn new(mass: f64, scale_length: f64, count: i32, radius_range: Range<f64>) -> ExponentialDisk {
+        ExponentialDisk {
+            mass,
+            scale_length,
+            count,
+            radius_range,
+            truncation: 5.0,
+            scale_height: 0.0,
+            bulge_mass: 0.0,
+            bulge_radius: 0.0,
+            halo: None,
+        }
+    }
+
+    /// Surface density at radius `r`.
+    pub fn surface_density(&self, r: f64) -> f64 {
+        return self.mass / (2.0 * PI * self.scale_length * self.scale_length) * f64::exp(-r / self.scale_length);
+    }
+
+    // height and vertical speed in a self-gravitating isothermal sheet, sigma_z^2 = pi G Sigma z0
+    fn vertical_state<F: Float, V: Vector<F>, R: Rng>(&self, rnd: &mut R, r: f64, gravity_const: f64) -> (f64, f64) {
+        if V::DIM == 2 || self.scale_height <= 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let u: f64 = rnd.gen_range(-1.0..1.0);
+        let height = self.scale_height * f64::atanh(u.clamp(-0.999_999, 0.999_999));
+        let dispersion = f64::sqrt(PI * gravity_const * self.surface_density(r) * self.scale_height);
+
+        // box-muller for a normal deviate
+        let u1: f64 = rnd.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = rnd.gen_range(0.0..2.0 * PI);
+        let normal = f64::sqrt(-2.0 * f64::ln(u1)) * f64::cos(u2);
+
+        return (height, dispersion * normal);
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for ExponentialDisk {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let gravity_const = world.gravity_const.to_f64();
+        let mut rnd = rand::thread_rng();
+        let count = self.count.max(0) as usize;
+        let body_mass = self.mass / count as f64;
+
+        // surface density e^(-R/h) gives R e^(-R/h) in radius, a gamma distribution of shape 2
+        let mut radii: Vec<f64> = Vec::with_capacity(count);
+        while radii.len() < count {
+            let u1: f64 = rnd.gen_range(0.0..1.0);
+            let u2: f64 = rnd.gen_range(0.0..1.0);
+            let r = -self.scale_length * f64::ln(u1 * u2);
+
+            if r.is_finite() && r > 0.0 && r <= self.truncation * self.scale_length {
+                radii.push(r);
+            }
+        }
+        radii.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        if self.bulge_mass > 0.0 {
+            spawn_body(world, Vec3::ZERO, Vec3::ZERO, self.bulge_mass, self.bulge_radius);
+        }
+
+        if let Some(halo) = &self.halo {
+            halo.spawn(world);
+        }
+
+        for (index, r) in radii.iter().enumerate() {
+            // disk mass is taken as spherically enclosed, against the thin disk curve of Freeman (1970)
+            // that overestimates rotation inside about one scale length and underestimates it beyond,
+            // by up to 13% around the peak at 2.2 scale lengths, so disks without a halo start slightly cold
+            let mut enclosed_mass = self.bulge_mass + body_mass * index as f64;
+            if let Some(halo) = &self.halo {
+                enclosed_mass += halo.enclosed_mass(*r);
+            }
+
+            let circular_velocity = f64::sqrt(gravity_const * enclosed_mass / r);
+            let direction = Vec3::new(1.0, 0.0, 0.0).rotate_z(rnd.gen_range(0.0..2.0 * PI));
+            let (height, vertical_velocity) = self.vertical_state::<F, V, _>(&mut rnd, *r, gravity_const);
+
+            spawn_body(
+                world,
+                direction * *r + Vec3::new(0.0, 0.0, height),
+                Vec3::new(-direction.y, direction.x, 0.0) * circular_velocity + Vec3::new(0.0, 0.0, vertical_velocity),
+                body_mass,
+                sample(&mut rnd, self.radius_range.clone()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::species::Species;
+    use super::super::super::vec2::Vec2;
+
+    // bodies by distance from the bulge at index 0, as (radius, tangential speed, radial speed)
+    fn orbits(world: &VerletWorld<f64, Vec2>) -> Vec<(f64, f64, f64)> {
+        let dt = world.sub_dt();
+        let center = world.objects.position(0);
+
+        let mut orbits: Vec<(f64, f64, f64)> = (1..world.objects.len()).map(|index| {
+            let offset = world.objects.position(index) - center;
+            let velocity = world.objects.velocity(index, dt) - world.objects.velocity(0, dt);
+            let r = offset.length();
+            let tangential = (offset.x * velocity.y - offset.y * velocity.x) / r;
+
+            (r, tangential, offset.dot(velocity) / r)
+        }).collect();
+        orbits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        return orbits;
+    }
+
+    #[test]
+    fn rotates_at_enclosed_mass_speed() {
+        // radii soften close encounters, which would otherwise heat the disk within an orbit
+        let mut disk = ExponentialDisk::new(100.0, 20.0, 200, 1.0..2.0);
+        disk.bulge_mass = 1000.0;
+        disk.bulge_radius = 1.0;
+
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 1.0e9);
+        world.dt = 0.05;
+        disk.spawn(&mut world);
+        let stars = world.add_species(Species { collides: false, ..Species::new("stars") });
+        world.objects.species.iter_mut().for_each(|species| *species = stars);
+
+        let body_mass = disk.mass / disk.count as f64;
+        for (index, (r, tangential, radial)) in orbits(&world).into_iter().enumerate() {
+            let expected = f64::sqrt(world.gravity_const * (disk.bulge_mass + body_mass * index as f64) / r);
+            assert!((tangential - expected).abs() < 1e-9 * expected, "speed {tangential} against {expected} at {r}");
+            assert!(radial.abs() < 1e-9 * expected);
+        }
+
+        // three orbits at the rotation peak
+        let peak = 2.2 * disk.scale_length;
+        let period = 2.0 * PI * peak / f64::sqrt(world.gravity_const * disk.bulge_mass / peak);
+        let start_radius = orbits(&world)[100].0;
+        while world.time() < 3.0 * period {
+            world.update();
+        }
+
+        let orbits = orbits(&world);
+        let rotation = orbits.iter().map(|orbit| orbit.1).sum::<f64>();
+        let speed = orbits.iter().map(|orbit| f64::hypot(orbit.1, orbit.2)).sum::<f64>();
+        assert!(rotation > 0.9 * speed, "rotation {rotation} against total speed {speed}");
+        // two-body relaxation among 200 bodies spreads the disk by some 20% over this time
+        assert!((orbits[100].0 / start_radius - 1.0).abs() < 0.4, "median radius {} from {start_radius}", orbits[100].0);
+    }
+}
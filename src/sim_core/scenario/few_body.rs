@@ -0,0 +1,251 @@
+use super::{Scenario, spawn_body};
+use super::kepler::OrbitalElements;
+use super::super::species::Species;
+use super::super::vec2::Vec2;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::verlet_object::VerletObject;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+const SPECIES_NAME: &str = "few body";
+
+/// Classic few-body test problems, all planar and defined in units where G = 1.
+#[derive(Copy, Clone)]
+pub enum Problem {
+    /// Chenciner & Montgomery (2000) choreography of three equal masses.
+    FigureEight,
+    /// Burrau's problem, masses 3, 4 and 5 at rest in the corners of a 3-4-5 triangle.
+    /// Szebehely & Peters (1967) find the lightest body ejected near t = 60 after approaches closer
+    /// than 1e-4, which a fixed step cannot follow, so the reference stops after the first close approach.
+    Pythagorean,
+    /// Two unit masses a unit distance apart.
+    CircularBinary,
+    /// Two unit masses on a Kepler orbit of unit semi-major axis, starting at periapsis.
+    EccentricBinary {
+        /// Orbit eccentricity, below one.
+        eccentricity: f64,
+    },
+}
+
+/// Expected positions of a problem's bodies after `time`.
+pub struct Reference {
+    /// World time at which the bodies reach `positions`.
+    pub time: f64,
+    /// The problem's length unit, scales the tolerance of `matches`.
+    pub length_unit: f64,
+    /// Body positions in world units, in spawn order.
+    pub positions: Vec<Vec2>,
+}
+
+/// A `Problem` scaled to world units.
+pub struct FewBody {
+    /// Which problem to set up.
+    pub problem: Problem,
+    /// World length of one problem unit.
+    pub length_unit: f64,
+    /// World mass of one problem unit.
+    pub mass_unit: f64,
+    /// Softens the bodies' gravity, they never collide since close encounters are the point.
+    pub radius: f64,
+}
+
+impl FewBody {
+    /// Problem with bodies a thousandth of the length unit in radius.
+    pub fn new(problem: Problem, length_unit: f64, mass_unit: f64) -> FewBody {
+        FewBody {
+            problem,
+            length_unit,
+            mass_unit,
+            radius: 0.001 * length_unit,
+        }
+    }
+
+    /// World time of one problem unit, following from the length and mass units and G.
+    pub fn time_unit(&self, gravity_const: f64) -> f64 {
+        return f64::sqrt(f64::powi(self.length_unit, 3) / (gravity_const * self.mass_unit));
+    }
+
+    /// World velocity of one problem unit.
+    pub fn velocity_unit(&self, gravity_const: f64) -> f64 {
+        return self.length_unit / self.time_unit(gravity_const);
+    }
+
+    /// Positions, velocities and masses in G = 1 units.
+    pub fn initial_conditions(&self) -> (Vec<Vec2>, Vec<Vec2>, Vec<f64>) {
+        match self.problem {
+            Problem::FigureEight => {
+                let position = Vec2::new(0.97000436, -0.24308753);
+                let velocity = Vec2::new(-0.93240737, -0.86473146);
+                let half_velocity = -velocity / 2.0;
+
+                return (
+                    vec![position, -position, Vec2::ZERO],
+                    vec![half_velocity, half_velocity, velocity],
+                    vec![1.0, 1.0, 1.0],
+                );
+            }
+            Problem::Pythagorean => {
+                return (
+                    vec![Vec2::new(1.0, 3.0), Vec2::new(-2.0, -1.0), Vec2::new(1.0, -1.0)],
+                    vec![Vec2::ZERO; 3],
+                    vec![3.0, 4.0, 5.0],
+                );
+            }
+            Problem::CircularBinary => {
+                return binary(0.0);
+            }
+            Problem::EccentricBinary { eccentricity } => {
+                return binary(eccentricity);
+            }
+        }
+    }
+
+    /// Expected positions in world units.
+    pub fn reference(&self, gravity_const: f64) -> Reference {
+        let time_unit = self.time_unit(gravity_const);
+        let (positions, _velocities, _masses) = self.initial_conditions();
+        let initial_positions: Vec<Vec2> = positions
+            .into_iter()
+            .map(|p| p * self.length_unit)
+            .collect();
+
+        let (time, positions) = match self.problem {
+            Problem::FigureEight => (6.32591398, initial_positions),
+            // past the approach to 0.0097 at t = 1.9, adaptive leapfrog converged to 1e-8
+            Problem::Pythagorean => {
+                let positions = vec![
+                    Vec2::new(0.37119923, -0.08547823),
+                    Vec2::new(-1.45387092, 0.03772350),
+                    Vec2::new(0.94037720, 0.02110814),
+                ];
+                (3.0, positions.into_iter().map(|p| p * self.length_unit).collect())
+            }
+            Problem::CircularBinary | Problem::EccentricBinary { .. } => {
+                (binary_elements(0.0).period(2.0), initial_positions)
+            }
+        };
+
+        return Reference {
+            time: time * time_unit,
+            length_unit: self.length_unit,
+            positions,
+        };
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for FewBody {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let velocity_unit = self.velocity_unit(world.gravity_const.to_f64());
+        let (positions, velocities, masses) = self.initial_conditions();
+        // problems composed into one world share the species
+        let species = match world.species.iter().position(|species| species.name == SPECIES_NAME) {
+            Some(index) => index as u32,
+            None => world.add_species(Species { collides: false, ..Species::new(SPECIES_NAME) }),
+        };
+
+        for i in 0..positions.len() {
+            spawn_body(
+                world,
+                Vec3::from(positions[i] * self.length_unit),
+                Vec3::from(velocities[i] * velocity_unit),
+                masses[i] * self.mass_unit,
+                self.radius,
+            );
+
+            let index = world.objects.len() - 1;
+            world.objects.species[index] = species;
+        }
+    }
+}
+
+impl Reference {
+    /// Checks bodies spawned at `first_index` of the world against the reference,
+    /// `tolerance` is relative to the problem's length unit.
+    pub fn matches<F: Float, V: Vector<F>>(&self, world: &VerletWorld<F, V>, first_index: usize, tolerance: f64) -> bool {
+        let bodies: Vec<VerletObject<F, V>> = world.objects.iter().skip(first_index).collect();
+
+        return self.positions.iter().zip(bodies.iter()).all(|(expected, body)| {
+            (body.position.to_vec3().cast() - Vec3::from(*expected)).length() <= tolerance * self.length_unit
+        });
+    }
+}
+
+fn binary_elements(eccentricity: f64) -> OrbitalElements {
+    return OrbitalElements {
+        semi_major_axis: 1.0,
+        eccentricity,
+        mass: 1.0,
+        ..OrbitalElements::default()
+    };
+}
+
+fn binary(eccentricity: f64) -> (Vec<Vec2>, Vec<Vec2>, Vec<f64>) {
+    let (position, velocity) = binary_elements(eccentricity).to_state(2.0);
+
+    // equal masses, so each body sits at half the relative vector around the barycentre
+    let position = position.xy() / 2.0;
+    let velocity = velocity.xy() / 2.0;
+
+    return (
+        vec![position, -position],
+        vec![velocity, -velocity],
+        vec![1.0, 1.0],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 1e-3;
+
+    // runs `problem` to its reference time on a step that lands on it exactly
+    fn run(problem: Problem, sub_steps: i32) -> bool {
+        let few_body = FewBody::new(problem, 50.0, 100.0);
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 1.0e9);
+        world.sub_steps = sub_steps;
+
+        // the step is fixed before spawning, the initial velocities are encoded with it
+        let reference = few_body.reference(world.gravity_const);
+        let steps = (reference.time / world.dt).ceil();
+        world.dt = reference.time / steps;
+        few_body.spawn(&mut world);
+        for _step in 0..steps as usize {
+            world.update();
+        }
+
+        return reference.matches(&world, 0, TOLERANCE);
+    }
+
+    #[test]
+    fn problems_share_one_species() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 1.0e9);
+        FewBody::new(Problem::FigureEight, 50.0, 100.0).spawn(&mut world);
+        FewBody::new(Problem::CircularBinary, 50.0, 100.0).spawn(&mut world);
+
+        assert_eq!(world.species.iter().filter(|species| species.name == SPECIES_NAME).count(), 1);
+        assert!(world.objects.species.iter().all(|species| *species == world.objects.species[0]));
+    }
+
+    #[test]
+    fn figure_eight() {
+        assert!(run(Problem::FigureEight, 10));
+    }
+
+    #[test]
+    fn pythagorean() {
+        // the close approach needs finer steps than the world's default
+        assert!(run(Problem::Pythagorean, 80));
+    }
+
+    #[test]
+    fn circular_binary() {
+        assert!(run(Problem::CircularBinary, 10));
+    }
+
+    #[test]
+    fn eccentric_binary() {
+        assert!(run(Problem::EccentricBinary { eccentricity: 0.6 }, 10));
+    }
+}
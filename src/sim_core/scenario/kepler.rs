@@ -0,0 +1,183 @@
+use std::f64::consts::PI;
+
+use super::{Scenario, spawn_body, to_center_of_mass_frame};
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+/// Elliptic orbit, angles in radians. With zero inclination the orbit lies in the xy plane
+/// and the ascending node only adds to the argument of periapsis.
+#[derive(Copy, Clone, Default)]
+pub struct OrbitalElements {
+    /// Half the orbit's long axis.
+    pub semi_major_axis: f64,
+    /// Zero for a circle, below one for an ellipse.
+    pub eccentricity: f64,
+    /// Tilt of the orbit against the xy plane, 2D worlds see the projected orbit.
+    pub inclination: f64,
+    /// Angle from the x axis to where the orbit rises through the xy plane.
+    pub longitude_of_ascending_node: f64,
+    /// Angle from the ascending node to periapsis, in the orbit plane.
+    pub argument_of_periapsis: f64,
+    /// Phase along the orbit, zero at periapsis.
+    pub mean_anomaly: f64,
+    /// Mass of the orbiting body.
+    pub mass: f64,
+    /// Radius of the orbiting body.
+    pub radius: f64,
+}
+
+impl OrbitalElements {
+    /// Mean angular velocity, `mu` as in `to_state`.
+    pub fn mean_motion(&self, mu: f64) -> f64 {
+        return f64::sqrt(mu / f64::powi(self.semi_major_axis, 3));
+    }
+
+    /// Orbital period, `mu` as in `to_state`.
+    pub fn period(&self, mu: f64) -> f64 {
+        return 2.0 * PI / self.mean_motion(mu);
+    }
+
+    /// Analytic solution of the two-body problem after the given time.
+    pub fn at_time(&self, mu: f64, time: f64) -> OrbitalElements {
+        let mut elements = *self;
+        elements.mean_anomaly = (self.mean_anomaly + self.mean_motion(mu) * time).rem_euclid(2.0 * PI);
+
+        return elements;
+    }
+
+    /// Position and velocity relative to the primary, `mu` is `G * (primary mass + body mass)`.
+    pub fn to_state(&self, mu: f64) -> (Vec3, Vec3) {
+        assert!(
+            self.eccentricity >= 0.0 && self.eccentricity < 1.0,
+            "only elliptic orbits are supported, got eccentricity {}",
+            self.eccentricity
+        );
+
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+        let eccentric_anomaly = solve_kepler(self.mean_anomaly, e);
+        let (sin_e, cos_e) = f64::sin_cos(eccentric_anomaly);
+        let minor_factor = f64::sqrt(1.0 - e * e);
+
+        let distance = a * (1.0 - e * cos_e);
+        let speed_factor = f64::sqrt(mu * a) / distance;
+
+        // perifocal frame, periapsis along +x
+        let position = Vec3::new(a * (cos_e - e), a * minor_factor * sin_e, 0.0);
+        let velocity = Vec3::new(-speed_factor * sin_e, speed_factor * minor_factor * cos_e, 0.0);
+
+        let orient = |v: Vec3| -> Vec3 {
+            return v
+                .rotate_z(self.argument_of_periapsis)
+                .rotate_x(self.inclination)
+                .rotate_z(self.longitude_of_ascending_node);
+        };
+
+        return (orient(position), orient(velocity));
+    }
+}
+
+/// Eccentric anomaly from mean anomaly, newton iterations on `E - e sin(E) = M`.
+pub fn solve_kepler(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mean_anomaly = mean_anomaly.rem_euclid(2.0 * PI);
+    let mut eccentric_anomaly = if eccentricity > 0.8 { PI } else { mean_anomaly };
+
+    for _iteration in 0..64 {
+        let delta = (eccentric_anomaly - eccentricity * f64::sin(eccentric_anomaly) - mean_anomaly)
+            / (1.0 - eccentricity * f64::cos(eccentric_anomaly));
+        eccentric_anomaly -= delta;
+
+        if f64::abs(delta) < 1e-14 {
+            break;
+        }
+    }
+
+    return eccentric_anomaly;
+}
+
+/// Central body with orbiting bodies, each orbit treated as an independent two-body problem.
+pub struct KeplerSystem {
+    /// Mass of the central body.
+    pub central_mass: f64,
+    /// Radius of the central body.
+    pub central_radius: f64,
+    /// Orbits around the central body, in spawn order after it.
+    pub bodies: Vec<OrbitalElements>,
+}
+
+impl KeplerSystem {
+    /// Central body without orbiting bodies.
+    pub fn new(central_mass: f64, central_radius: f64) -> KeplerSystem {
+        KeplerSystem {
+            central_mass,
+            central_radius,
+            bodies: Vec::new(),
+        }
+    }
+
+    /// Adds an orbiting body.
+    pub fn add(&mut self, elements: OrbitalElements) -> &mut Self {
+        self.bodies.push(elements);
+        return self;
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for KeplerSystem {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let gravity_const = world.gravity_const.to_f64();
+        let mut positions: Vec<Vec3> = vec![Vec3::ZERO];
+        let mut velocities: Vec<Vec3> = vec![Vec3::ZERO];
+        let mut masses: Vec<f64> = vec![self.central_mass];
+        let mut radii: Vec<f64> = vec![self.central_radius];
+
+        for body in self.bodies.iter() {
+            let (position, velocity) = body.to_state(gravity_const * (self.central_mass + body.mass));
+
+            positions.push(position);
+            velocities.push(velocity);
+            masses.push(body.mass);
+            radii.push(body.radius);
+        }
+
+        // elements are relative to the central body, the world gets the barycentric frame
+        to_center_of_mass_frame(&mut positions, &mut velocities, &masses);
+
+        for i in 0..positions.len() {
+            spawn_body(world, positions[i], velocities[i], masses[i], radii[i]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::vec2::Vec2;
+
+    #[test]
+    fn matches_two_body_solution_after_one_period() {
+        for eccentricity in [0.0, 0.5] {
+            let elements = OrbitalElements {
+                semi_major_axis: 20.0,
+                eccentricity,
+                mass: 1.0,
+                radius: 0.1,
+                ..OrbitalElements::default()
+            };
+            let mut system = KeplerSystem::new(1000.0, 1.0);
+            system.add(elements);
+
+            let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+            system.spawn(&mut world);
+            let mu = world.gravity_const * (system.central_mass + elements.mass);
+            while world.time() < elements.period(mu) {
+                world.update();
+            }
+
+            let (expected, _velocity) = elements.at_time(mu, world.time()).to_state(mu);
+            let separation = (world.objects.position(1) - world.objects.position(0)).to_vec3();
+            assert!((separation - expected).length() < 1e-3 * elements.semi_major_axis, "eccentricity {eccentricity}: {separation:?} against {expected:?}");
+        }
+    }
+}
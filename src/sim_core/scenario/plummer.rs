@@ -0,0 +1,145 @@
+use std::ops::Range;
+use rand::Rng;
+
+use super::{Scenario, spawn_body, project, random_direction, to_center_of_mass_frame, kinetic_energy, potential_energy};
+use super::super::emitter::sample;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+/// Plummer star cluster, sampled as in Aarseth, Henon & Wielen (1974), projected onto the plane in 2D worlds.
+pub struct Plummer {
+    /// Total mass, shared equally by the bodies.
+    pub mass: f64,
+    /// Plummer radius, inside which the density is roughly flat.
+    pub scale_radius: f64,
+    /// Number of bodies.
+    pub count: i32,
+    /// Body radii are drawn uniformly from this range.
+    pub radius_range: Range<f64>,
+    /// Bodies further than `truncation * scale_radius` are resampled.
+    pub truncation: f64,
+}
+
+impl Plummer {
+    /// Cluster truncated at ten scale radii.
+    pub fn new(mass: f64, scale_radius: f64, count: i32, radius_range: Range<f64>) -> Plummer {
+        Plummer {
+            mass,
+            scale_radius,
+            count,
+            radius_range,
+            truncation: 10.0,
+        }
+    }
+
+    /// Mass within the 3D radius `r`.
+    pub fn enclosed_mass(&self, r: f64) -> f64 {
+        let r_squared = r * r;
+        return self.mass * r_squared * r / f64::powf(r_squared + self.scale_radius * self.scale_radius, 1.5);
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for Plummer {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let gravity_const = world.gravity_const.to_f64();
+        let mut rnd = rand::thread_rng();
+        let count = self.count.max(0) as usize;
+        let body_mass = self.mass / count as f64;
+
+        let mut positions: Vec<Vec3> = Vec::with_capacity(count);
+        let mut velocities: Vec<Vec3> = Vec::with_capacity(count);
+        let masses: Vec<f64> = vec![body_mass; count];
+
+        while positions.len() < count {
+            // radius from the inverted cumulative mass profile
+            let mass_fraction: f64 = rnd.gen_range(0.0..1.0);
+            if mass_fraction <= 0.0 {
+                continue;
+            }
+
+            let r = self.scale_radius / f64::sqrt(f64::powf(mass_fraction, -2.0 / 3.0) - 1.0);
+            if r > self.truncation * self.scale_radius {
+                continue;
+            }
+
+            // speed as a fraction of the local escape velocity, rejection sampled from q^2 (1 - q^2)^3.5
+            let escape_velocity = f64::sqrt(2.0 * gravity_const * self.mass / f64::sqrt(r * r + self.scale_radius * self.scale_radius));
+            let q = loop {
+                let q: f64 = rnd.gen_range(0.0..1.0);
+                let g: f64 = rnd.gen_range(0.0..0.1);
+                if g < q * q * f64::powf(1.0 - q * q, 3.5) {
+                    break q;
+                }
+            };
+
+            let position = random_direction(&mut rnd) * r;
+            let velocity = random_direction(&mut rnd) * (q * escape_velocity);
+
+            positions.push(project::<F, V>(position));
+            velocities.push(project::<F, V>(velocity));
+        }
+
+        to_center_of_mass_frame(&mut positions, &mut velocities, &masses);
+
+        // projection changes both energies, so rescale speeds back to 2K = -W on the projected set,
+        // in 3D this only corrects the sampling noise
+        let kinetic = kinetic_energy(&velocities, &masses);
+        let potential = potential_energy(&positions, &masses, gravity_const);
+        let virial_factor = if kinetic > 0.0 { f64::sqrt(-potential / (2.0 * kinetic)) } else { 0.0 };
+
+        for i in 0..count {
+            spawn_body(
+                world,
+                positions[i],
+                velocities[i] * virial_factor,
+                masses[i],
+                sample(&mut rnd, self.radius_range.clone()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::species::Species;
+
+    // 2K / |W| of the world's bodies
+    fn virial_ratio(world: &VerletWorld<f64, Vec3>) -> f64 {
+        let dt = world.sub_dt();
+        let objects = &world.objects;
+        let mut kinetic = 0.0;
+        let mut potential = 0.0;
+
+        for i in 0..objects.len() {
+            kinetic += 0.5 * objects.mass[i] * objects.velocity(i, dt).length_square();
+            for j in i + 1..objects.len() {
+                potential -= world.gravity_const * objects.mass[i] * objects.mass[j] / (objects.position(i) - objects.position(j)).length();
+            }
+        }
+
+        return 2.0 * kinetic / -potential;
+    }
+
+    #[test]
+    fn stays_in_virial_equilibrium() {
+        let plummer = Plummer::new(1000.0, 10.0, 200, 0.1..0.2);
+        let mut world = VerletWorld::<f64, Vec3>::new(0, 1000.0);
+        plummer.spawn(&mut world);
+        let stars = world.add_species(Species { collides: false, ..Species::new("stars") });
+        world.objects.species.iter_mut().for_each(|species| *species = stars);
+
+        // virial radius 16 a / 3 pi over the rms speed
+        let speed = f64::sqrt(3.0 * std::f64::consts::PI * world.gravity_const * plummer.mass / (32.0 * plummer.scale_radius));
+        let crossing_time = 16.0 * plummer.scale_radius / (3.0 * std::f64::consts::PI) / speed;
+
+        assert!((virial_ratio(&world) - 1.0).abs() < 0.05);
+        while world.time() < 4.0 * crossing_time {
+            world.update();
+            let ratio = virial_ratio(&world);
+            assert!((ratio - 1.0).abs() < 0.15, "virial ratio {ratio} at {} crossing times", world.time() / crossing_time);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+use rand::Rng;
+
+use super::{Scenario, spawn_body, to_center_of_mass_frame};
+use super::super::emitter::sample;
+use super::super::vec3::Vec3;
+use super::super::vector::Vector;
+use super::super::float::Float;
+use super::super::verlet_world::VerletWorld;
+
+/// Central star with a flat keplerian disk of planetesimals in the xy plane,
+/// surface density falls off as `R^-power_law`.
+pub struct ProtoplanetaryDisk {
+    /// Mass of the central star.
+    pub star_mass: f64,
+    /// Radius of the central star.
+    pub star_radius: f64,
+    /// A fixed star is pinned to the origin, a live one recoils from the disk.
+    pub star_fixed: bool,
+
+    /// Total mass of the planetesimals, shared equally.
+    pub disk_mass: f64,
+    /// Inner edge of the disk.
+    pub inner_radius: f64,
+    /// Outer edge of the disk.
+    pub outer_radius: f64,
+    /// Exponent of the surface density fall off.
+    pub power_law: f64,
+    /// Number of planetesimals.
+    pub count: i32,
+    /// Planetesimal radii are drawn uniformly from this range.
+    pub radius_range: Range<f64>,
+}
+
+impl ProtoplanetaryDisk {
+    /// Live star and a minimum mass solar nebula profile, `power_law` 1.5.
+    pub fn new(star_mass: f64, star_radius: f64, disk_mass: f64, inner_radius: f64, outer_radius: f64, count: i32, radius_range: Range<f64>) -> ProtoplanetaryDisk {
+        ProtoplanetaryDisk {
+            star_mass,
+            star_radius,
+            star_fixed: false,
+            disk_mass,
+            inner_radius,
+            outer_radius,
+            // minimum mass solar nebula
+            power_law: 1.5,
+            count,
+            radius_range,
+        }
+    }
+
+    // inverted cumulative distribution of R^(1 - p) between the inner and outer radius
+    fn sample_radius(&self, u: f64) -> f64 {
+        let exponent = 2.0 - self.power_law;
+
+        if f64::abs(exponent) < 1e-9 {
+            return self.inner_radius * f64::powf(self.outer_radius / self.inner_radius, u);
+        }
+
+        let inner = f64::powf(self.inner_radius, exponent);
+        let outer = f64::powf(self.outer_radius, exponent);
+
+        return f64::powf(inner + u * (outer - inner), 1.0 / exponent);
+    }
+}
+
+impl<F: Float, V: Vector<F>> Scenario<F, V> for ProtoplanetaryDisk {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        let gravity_const = world.gravity_const.to_f64();
+        let mut rnd = rand::thread_rng();
+        let count = self.count.max(0) as usize;
+        let body_mass = self.disk_mass / count as f64;
+
+        let mut positions: Vec<Vec3> = vec![Vec3::ZERO];
+        let mut velocities: Vec<Vec3> = vec![Vec3::ZERO];
+        let mut masses: Vec<f64> = vec![self.star_mass];
+        let mut radii: Vec<f64> = vec![self.star_radius];
+
+        let mut distances: Vec<f64> = (0..count).map(|_| self.sample_radius(rnd.gen_range(0.0..1.0))).collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        for (index, r) in distances.iter().enumerate() {
+            let enclosed_mass = self.star_mass + body_mass * index as f64;
+            let circular_velocity = f64::sqrt(gravity_const * enclosed_mass / r);
+            let direction = Vec3::new(1.0, 0.0, 0.0).rotate_z(rnd.gen_range(0.0..2.0 * PI));
+
+            positions.push(direction * *r);
+            velocities.push(Vec3::new(-direction.y, direction.x, 0.0) * circular_velocity);
+            masses.push(body_mass);
+            radii.push(sample(&mut rnd, self.radius_range.clone()));
+        }
+
+        // a live star recoils from the disk, a fixed one holds the origin
+        if !self.star_fixed {
+            to_center_of_mass_frame(&mut positions, &mut velocities, &masses);
+        }
+
+        let star_index = world.objects.len();
+        for i in 0..positions.len() {
+            spawn_body(world, positions[i], velocities[i], masses[i], radii[i]);
+        }
+        world.objects.fixed[star_index] = self.star_fixed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::super::vec2::Vec2;
+
+    #[test]
+    fn follows_surface_density_power_law() {
+        for power_law in [1.5, 2.0] {
+            let mut disk = ProtoplanetaryDisk::new(1000.0, 5.0, 10.0, 20.0, 200.0, 4000, 0.5..0.5);
+            disk.power_law = power_law;
+            let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+            disk.spawn(&mut world);
+
+            let star = world.objects.position(0);
+            let distances: Vec<f64> = (1..world.objects.len()).map(|index| (world.objects.position(index) - star).length()).collect();
+
+            // mass within R grows as R^(2 - p) from the inner edge, ln R at p = 2
+            let cumulative = |r: f64| -> f64 {
+                if power_law == 2.0 {
+                    return f64::ln(r / disk.inner_radius) / f64::ln(disk.outer_radius / disk.inner_radius);
+                }
+
+                let exponent = 2.0 - power_law;
+                return (r.powf(exponent) - disk.inner_radius.powf(exponent)) / (disk.outer_radius.powf(exponent) - disk.inner_radius.powf(exponent));
+            };
+
+            for r in [30.0, 50.0, 100.0, 150.0] {
+                let fraction = distances.iter().filter(|distance| **distance < r).count() as f64 / distances.len() as f64;
+                assert!((fraction - cumulative(r)).abs() < 0.03, "power {power_law}: {fraction} within {r}, expected {}", cumulative(r));
+            }
+        }
+    }
+
+    #[test]
+    fn live_star_sits_in_center_of_mass_frame() {
+        for star_fixed in [false, true] {
+            let mut disk = ProtoplanetaryDisk::new(1000.0, 5.0, 100.0, 20.0, 200.0, 200, 0.5..1.0);
+            disk.star_fixed = star_fixed;
+            let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+            disk.spawn(&mut world);
+
+            let dt = world.sub_dt();
+            let objects = &world.objects;
+            let momentum = (0..objects.len()).fold(Vec2::ZERO, |sum, index| sum + objects.velocity(index, dt) * objects.mass[index]);
+            let moment = (0..objects.len()).fold(Vec2::ZERO, |sum, index| sum + objects.position(index) * objects.mass[index]);
+
+            assert_eq!(objects.fixed[0], star_fixed);
+            if star_fixed {
+                assert_eq!(objects.position(0), Vec2::ZERO);
+                assert_eq!(objects.velocity(0, dt), Vec2::ZERO);
+            } else {
+                assert!(momentum.length() < 1e-9 * disk.star_mass);
+                assert!(moment.length() < 1e-9 * disk.star_mass);
+                assert!(objects.velocity(0, dt).length() > 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::float::Float;
+use super::particles::Particles;
+use super::scenario::Scenario;
+use super::vec2::Vec2;
+use super::vector::Vector;
+use super::verlet_world::VerletWorld;
+
+/// Where a `Sink` removes bodies.
+#[derive(Copy, Clone, Debug)]
+pub enum Region<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Everything further than `radius` from `center`, e.g. an absorbing outer boundary.
+    Beyond {
+        /// Centre of the kept ball.
+        center: V,
+        /// Radius of the kept ball.
+        radius: F,
+    },
+    /// Everything within `radius` of `center`.
+    Within {
+        /// Centre of the absorbing ball.
+        center: V,
+        /// Radius of the absorbing ball.
+        radius: F,
+    },
+    /// Everything within `radius` of the body with `id`, e.g. a black hole's accretion radius.
+    /// The body itself stays and the region is empty once it is gone.
+    AroundBody {
+        /// Stable id of the body, see `Particles::id`.
+        id: u64,
+        /// Radius of the absorbing ball around the body.
+        radius: F,
+    },
+}
+
+/// Region that removes the bodies entering it and keeps a tally of what it took.
+#[derive(Clone, Debug)]
+pub struct Sink<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Where bodies are removed.
+    pub region: Region<F, V>,
+
+    /// Number of bodies removed so far.
+    pub removed_count: usize,
+    /// Total mass of the removed bodies.
+    pub removed_mass: F,
+    /// Total momentum of the removed bodies, each at its last velocity.
+    pub removed_momentum: V,
+    /// Ids of the removed bodies in the order they went.
+    pub removed_ids: Vec<u64>,
+}
+
+impl<F: Float, V: Vector<F>> Sink<F, V> {
+    /// Sink with an empty tally.
+    pub fn new(region: Region<F, V>) -> Sink<F, V> {
+        Sink {
+            region,
+            removed_count: 0,
+            removed_mass: F::ZERO,
+            removed_momentum: V::ZERO,
+            removed_ids: Vec::new(),
+        }
+    }
+
+    /// Whether the body at `index` is inside the region.
+    pub fn contains(&self, objects: &Particles<F, V>, index: usize) -> bool {
+        let position = objects.position(index);
+
+        match self.region {
+            Region::Beyond { center, radius } => {
+                return (position - center).length_square() > radius * radius;
+            }
+            Region::Within { center, radius } => {
+                return (position - center).length_square() < radius * radius;
+            }
+            Region::AroundBody { id, radius } => {
+                if objects.id(index) == id {
+                    return false;
+                }
+
+                return match objects.index_of(id) {
+                    Some(body) => (position - objects.position(body)).length_square() < radius * radius,
+                    None => false,
+                };
+            }
+        }
+    }
+
+    /// Adds a body of `mass` moving with `velocity` to the tally.
+    pub fn record(&mut self, id: u64, mass: F, velocity: V) {
+        self.removed_count += 1;
+        self.removed_mass += mass;
+        self.removed_momentum += velocity * mass;
+        self.removed_ids.push(id);
+    }
+}
+
+// scenarios add sinks alongside their initial bodies
+impl<F: Float, V: Vector<F>> Scenario<F, V> for Sink<F, V> {
+    fn spawn(&self, world: &mut VerletWorld<F, V>) {
+        world.sinks.push(self.clone());
+    }
+}
@@ -0,0 +1,45 @@
+use super::float::Float;
+
+/// Rules for every body whose `species` indexes this entry in `VerletWorld::species`,
+/// bodies of unlisted species follow `Species::default`.
+#[derive(Clone, Debug)]
+pub struct Species<F: Float = f64> {
+    /// Label for the caller, the world never reads it.
+    pub name: String,
+    /// Bodies that do not collide pass through everything, bodies of other species included.
+    pub collides: bool,
+    /// Bodies that do not source gravity still feel it, e.g. gas tracers or test particles.
+    pub sources_gravity: bool,
+    /// Render color, `None` keeps the temperature shading.
+    pub color: Option<[u8; 3]>,
+    /// Overrides each body's `friction_factor` at the start of every update.
+    pub friction: Option<F>,
+}
+
+impl<F: Float> Default for Species<F> {
+    fn default() -> Self {
+        return Species::new("default");
+    }
+}
+
+impl<F: Float> Species<F> {
+    /// Colliding, gravitating species that leaves color and friction to the bodies.
+    pub fn new(name: &str) -> Species<F> {
+        Species {
+            name: name.to_owned(),
+            collides: true,
+            sources_gravity: true,
+            color: None,
+            friction: None,
+        }
+    }
+
+    /// Feels gravity and nothing else.
+    pub fn test_particle(name: &str) -> Species<F> {
+        Species {
+            collides: false,
+            sources_gravity: false,
+            ..Species::new(name)
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vector::Vector;
+
+/// Massless test particles kept apart from `VerletWorld::objects`.
+///
+/// Tracers feel the gravity of the bodies but exert none, never collide and skip the grid,
+/// so millions of them stay cheap. They accelerate by G * M / r^2 like a body of zero radius
+/// would, which makes them follow the field of the bodies, e.g. rings or potential flow.
+///
+/// The world moves them with a single step of `VerletWorld::step_dt` per update, coarser than
+/// the `sub_steps` integrations the bodies take, so `velocity` wants that step too and orbits
+/// tighter than a few steps per turn come out less accurate than the bodies'.
+/// Sinks, the constraint radius and lifetimes leave tracers alone, they only go away
+/// through `swap_remove`.
+#[derive(Clone, Default)]
+pub struct Tracers<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Per tracer positions, indexed like the other arrays.
+    pub position: Vec<V>,
+    /// Per tracer positions one step ago, the difference is the velocity.
+    pub position_last: Vec<V>,
+    /// Per tracer accelerations for the next step.
+    pub acceleration: Vec<V>,
+
+    float: PhantomData<F>,
+}
+
+impl<F: Float, V: Vector<F>> Tracers<F, V> {
+    /// Empty set of tracers.
+    pub fn new() -> Tracers<F, V> {
+        return Tracers::default();
+    }
+
+    /// Number of tracers.
+    pub fn len(&self) -> usize {
+        return self.position.len();
+    }
+
+    /// Whether there are no tracers.
+    pub fn is_empty(&self) -> bool {
+        return self.position.is_empty();
+    }
+
+    /// Adds a tracer moving with `velocity`, where `dt` is the integration step.
+    pub fn push(&mut self, position: V, velocity: V, dt: F) -> &mut Self {
+        self.position.push(position);
+        self.position_last.push(position - velocity * dt);
+        self.acceleration.push(V::ZERO);
+
+        return self;
+    }
+
+    /// Removes the tracer at `index` and moves the last one into its place, returns its position.
+    pub fn swap_remove(&mut self, index: usize) -> V {
+        self.position_last.swap_remove(index);
+        self.acceleration.swap_remove(index);
+        return self.position.swap_remove(index);
+    }
+
+    /// Velocity over the last integration step of length `dt`.
+    pub fn velocity(&self, index: usize, dt: F) -> V {
+        return (self.position[index] - self.position_last[index]) / dt;
+    }
+
+    /// Position verlet step without friction, clears the accelerations.
+    pub fn integrate(&mut self, dt: F) -> &mut Self {
+        let dt_squared = dt * dt;
+
+        for i in 0..self.position.len() {
+            let velocity = self.position[i] - self.position_last[i];
+            self.position_last[i] = self.position[i];
+            self.position[i] += velocity + self.acceleration[i] * dt_squared;
+            self.acceleration[i] = V::ZERO;
+        }
+
+        return self;
+    }
+}
@@ -0,0 +1,153 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::float::Float;
+
+/// 2D vector used for positions, velocities and accelerations.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Vec2<F = f64> {
+    /// Horizontal component.
+    pub x: F,
+    /// Vertical component.
+    pub y: F,
+}
+
+impl<F: Float> Vec2<F> {
+    /// Both components zero.
+    pub const ZERO: Vec2<F> = Vec2 { x: F::ZERO, y: F::ZERO };
+
+    /// Vector from its components.
+    pub fn new(x: F, y: F) -> Vec2<F> {
+        Vec2 { x, y }
+    }
+
+    /// Scalar product.
+    pub fn dot(self, other: Vec2<F>) -> F {
+        return self.x * other.x + self.y * other.y;
+    }
+
+    /// Z component of the 3D cross product.
+    pub fn cross(self, other: Vec2<F>) -> F {
+        return self.x * other.y - self.y * other.x;
+    }
+
+    /// Squared length, without the square root.
+    pub fn length_square(self) -> F {
+        return self.dot(self);
+    }
+
+    /// Euclidean length.
+    pub fn length(self) -> F {
+        return self.length_square().sqrt();
+    }
+
+    /// Unit vector in the same direction, the zero vector stays zero.
+    pub fn normalize(self) -> Vec2<F> {
+        let length = self.length();
+        if length > F::ZERO {
+            return self / length;
+        }
+
+        return Vec2::ZERO;
+    }
+
+    /// Rotated by a quarter turn counter-clockwise.
+    pub fn perpendicular(self) -> Vec2<F> {
+        return Vec2::new(-self.y, self.x);
+    }
+
+    /// Rotated counter-clockwise by `angle` radians.
+    pub fn rotate(self, angle: F) -> Vec2<F> {
+        let (sin, cos) = angle.sin_cos();
+        return Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos);
+    }
+
+    /// Whether both components are finite.
+    pub fn is_finite(self) -> bool {
+        return self.x.is_finite() && self.y.is_finite();
+    }
+
+    /// Converts between precisions.
+    pub fn cast<G: Float>(self) -> Vec2<G> {
+        return Vec2::new(G::from_f64(self.x.to_f64()), G::from_f64(self.y.to_f64()));
+    }
+}
+
+impl<F: Float> Add for Vec2<F> {
+    type Output = Vec2<F>;
+
+    fn add(self, other: Vec2<F>) -> Vec2<F> {
+        return Vec2::new(self.x + other.x, self.y + other.y);
+    }
+}
+
+impl<F: Float> Sub for Vec2<F> {
+    type Output = Vec2<F>;
+
+    fn sub(self, other: Vec2<F>) -> Vec2<F> {
+        return Vec2::new(self.x - other.x, self.y - other.y);
+    }
+}
+
+impl<F: Float> Mul<F> for Vec2<F> {
+    type Output = Vec2<F>;
+
+    fn mul(self, v: F) -> Vec2<F> {
+        return Vec2::new(self.x * v, self.y * v);
+    }
+}
+
+impl Mul<Vec2<f32>> for f32 {
+    type Output = Vec2<f32>;
+
+    fn mul(self, v: Vec2<f32>) -> Vec2<f32> {
+        return v * self;
+    }
+}
+
+impl Mul<Vec2<f64>> for f64 {
+    type Output = Vec2<f64>;
+
+    fn mul(self, v: Vec2<f64>) -> Vec2<f64> {
+        return v * self;
+    }
+}
+
+impl<F: Float> Div<F> for Vec2<F> {
+    type Output = Vec2<F>;
+
+    fn div(self, v: F) -> Vec2<F> {
+        return Vec2::new(self.x / v, self.y / v);
+    }
+}
+
+impl<F: Float> Neg for Vec2<F> {
+    type Output = Vec2<F>;
+
+    fn neg(self) -> Vec2<F> {
+        return Vec2::new(-self.x, -self.y);
+    }
+}
+
+impl<F: Float> AddAssign for Vec2<F> {
+    fn add_assign(&mut self, other: Vec2<F>) {
+        *self = *self + other;
+    }
+}
+
+impl<F: Float> SubAssign for Vec2<F> {
+    fn sub_assign(&mut self, other: Vec2<F>) {
+        *self = *self - other;
+    }
+}
+
+impl<F: Float> MulAssign<F> for Vec2<F> {
+    fn mul_assign(&mut self, v: F) {
+        *self = *self * v;
+    }
+}
+
+impl<F: Float> DivAssign<F> for Vec2<F> {
+    fn div_assign(&mut self, v: F) {
+        *self = *self / v;
+    }
+}
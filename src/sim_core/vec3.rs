@@ -0,0 +1,179 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::float::Float;
+use super::vec2::Vec2;
+
+/// 3D vector used for positions, velocities and accelerations of 3D worlds.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Vec3<F = f64> {
+    /// Component along x.
+    pub x: F,
+    /// Component along y.
+    pub y: F,
+    /// Component along z, zero for points in the plane.
+    pub z: F,
+}
+
+impl<F: Float> Vec3<F> {
+    /// All components zero.
+    pub const ZERO: Vec3<F> = Vec3 { x: F::ZERO, y: F::ZERO, z: F::ZERO };
+
+    /// Vector from its components.
+    pub fn new(x: F, y: F, z: F) -> Vec3<F> {
+        Vec3 { x, y, z }
+    }
+
+    /// Scalar product.
+    pub fn dot(self, other: Vec3<F>) -> F {
+        return self.x * other.x + self.y * other.y + self.z * other.z;
+    }
+
+    /// Vector product, right-handed.
+    pub fn cross(self, other: Vec3<F>) -> Vec3<F> {
+        return Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        );
+    }
+
+    /// Squared length, without the square root.
+    pub fn length_square(self) -> F {
+        return self.dot(self);
+    }
+
+    /// Euclidean length.
+    pub fn length(self) -> F {
+        return self.length_square().sqrt();
+    }
+
+    /// Unit vector in the same direction, the zero vector stays zero.
+    pub fn normalize(self) -> Vec3<F> {
+        let length = self.length();
+        if length > F::ZERO {
+            return self / length;
+        }
+
+        return Vec3::ZERO;
+    }
+
+    /// Rotated counter-clockwise by `angle` radians around the x axis.
+    pub fn rotate_x(self, angle: F) -> Vec3<F> {
+        let (sin, cos) = angle.sin_cos();
+        return Vec3::new(self.x, self.y * cos - self.z * sin, self.y * sin + self.z * cos);
+    }
+
+    /// Rotated counter-clockwise by `angle` radians around the y axis.
+    pub fn rotate_y(self, angle: F) -> Vec3<F> {
+        let (sin, cos) = angle.sin_cos();
+        return Vec3::new(self.x * cos + self.z * sin, self.y, self.z * cos - self.x * sin);
+    }
+
+    /// Rotated counter-clockwise by `angle` radians around the z axis, like `Vec2::rotate`.
+    pub fn rotate_z(self, angle: F) -> Vec3<F> {
+        let (sin, cos) = angle.sin_cos();
+        return Vec3::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos, self.z);
+    }
+
+    /// Projection onto the xy plane.
+    pub fn xy(self) -> Vec2<F> {
+        return Vec2::new(self.x, self.y);
+    }
+
+    /// Whether all components are finite.
+    pub fn is_finite(self) -> bool {
+        return self.x.is_finite() && self.y.is_finite() && self.z.is_finite();
+    }
+
+    /// Converts between precisions.
+    pub fn cast<G: Float>(self) -> Vec3<G> {
+        return Vec3::new(G::from_f64(self.x.to_f64()), G::from_f64(self.y.to_f64()), G::from_f64(self.z.to_f64()));
+    }
+}
+
+/// Lifts a plane vector to `z = 0`.
+impl<F: Float> From<Vec2<F>> for Vec3<F> {
+    fn from(v: Vec2<F>) -> Vec3<F> {
+        return Vec3::new(v.x, v.y, F::ZERO);
+    }
+}
+
+impl<F: Float> Add for Vec3<F> {
+    type Output = Vec3<F>;
+
+    fn add(self, other: Vec3<F>) -> Vec3<F> {
+        return Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z);
+    }
+}
+
+impl<F: Float> Sub for Vec3<F> {
+    type Output = Vec3<F>;
+
+    fn sub(self, other: Vec3<F>) -> Vec3<F> {
+        return Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z);
+    }
+}
+
+impl<F: Float> Mul<F> for Vec3<F> {
+    type Output = Vec3<F>;
+
+    fn mul(self, v: F) -> Vec3<F> {
+        return Vec3::new(self.x * v, self.y * v, self.z * v);
+    }
+}
+
+impl Mul<Vec3<f32>> for f32 {
+    type Output = Vec3<f32>;
+
+    fn mul(self, v: Vec3<f32>) -> Vec3<f32> {
+        return v * self;
+    }
+}
+
+impl Mul<Vec3<f64>> for f64 {
+    type Output = Vec3<f64>;
+
+    fn mul(self, v: Vec3<f64>) -> Vec3<f64> {
+        return v * self;
+    }
+}
+
+impl<F: Float> Div<F> for Vec3<F> {
+    type Output = Vec3<F>;
+
+    fn div(self, v: F) -> Vec3<F> {
+        return Vec3::new(self.x / v, self.y / v, self.z / v);
+    }
+}
+
+impl<F: Float> Neg for Vec3<F> {
+    type Output = Vec3<F>;
+
+    fn neg(self) -> Vec3<F> {
+        return Vec3::new(-self.x, -self.y, -self.z);
+    }
+}
+
+impl<F: Float> AddAssign for Vec3<F> {
+    fn add_assign(&mut self, other: Vec3<F>) {
+        *self = *self + other;
+    }
+}
+
+impl<F: Float> SubAssign for Vec3<F> {
+    fn sub_assign(&mut self, other: Vec3<F>) {
+        *self = *self - other;
+    }
+}
+
+impl<F: Float> MulAssign<F> for Vec3<F> {
+    fn mul_assign(&mut self, v: F) {
+        *self = *self * v;
+    }
+}
+
+impl<F: Float> DivAssign<F> for Vec3<F> {
+    fn div_assign(&mut self, v: F) {
+        *self = *self / v;
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+
+/// Vector type a world runs in, `Vec2` for a plane and `Vec3` for space.
+pub trait Vector<F: Float>:
+    Copy
+    + Debug
+    + Default
+    + PartialEq
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<F, Output = Self>
+    + Div<F, Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign<F>
+    + DivAssign<F>
+{
+    /// Number of spatial dimensions, 2 or 3.
+    const DIM: usize;
+    /// All components zero.
+    const ZERO: Self;
+
+    /// Scalar product.
+    fn dot(self, other: Self) -> F;
+    /// Whether every component is finite.
+    fn is_finite(self) -> bool;
+
+    /// Lifts to 3D, a plane vector gets `z = 0`.
+    fn to_vec3(self) -> Vec3<F>;
+    /// Drops the components the type does not have, a plane vector loses `z`.
+    fn from_vec3(v: Vec3<F>) -> Self;
+
+    /// Squared length, without the square root.
+    fn length_square(self) -> F {
+        return self.dot(self);
+    }
+
+    /// Euclidean length.
+    fn length(self) -> F {
+        return self.length_square().sqrt();
+    }
+}
+
+impl<F: Float> Vector<F> for Vec2<F> {
+    const DIM: usize = 2;
+    const ZERO: Vec2<F> = Vec2::ZERO;
+
+    fn dot(self, other: Vec2<F>) -> F {
+        return Vec2::dot(self, other);
+    }
+
+    fn is_finite(self) -> bool {
+        return Vec2::is_finite(self);
+    }
+
+    fn to_vec3(self) -> Vec3<F> {
+        return Vec3::from(self);
+    }
+
+    fn from_vec3(v: Vec3<F>) -> Vec2<F> {
+        return v.xy();
+    }
+}
+
+impl<F: Float> Vector<F> for Vec3<F> {
+    const DIM: usize = 3;
+    const ZERO: Vec3<F> = Vec3::ZERO;
+
+    fn dot(self, other: Vec3<F>) -> F {
+        return Vec3::dot(self, other);
+    }
+
+    fn is_finite(self) -> bool {
+        return Vec3::is_finite(self);
+    }
+
+    fn to_vec3(self) -> Vec3<F> {
+        return self;
+    }
+
+    fn from_vec3(v: Vec3<F>) -> Vec3<F> {
+        return v;
+    }
+}
@@ -1,75 +1,135 @@
-use super::point::Point;
+use super::float::Float;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
 use rand::Rng;
 
+/// Body integrated with position verlet, its velocity is implied by `position - position_last`.
 #[derive(Copy, Clone)]
-pub struct VerletObject {
-    pub position: Point,
-    pub position_last: Point,
-    pub acceleration: Point,
-    pub mass: f64,
-    pub radius: f64,
-    pub temp: f64,
-    pub friction_factor: f64
+pub struct VerletObject<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Current position.
+    pub position: V,
+    /// Position one integration step ago.
+    pub position_last: V,
+    /// Acceleration summed for the next integration step, cleared by it.
+    pub acceleration: V,
+    /// Mass, also the weight in collisions.
+    pub mass: F,
+    /// Collision radius, also the gravity softening length.
+    pub radius: F,
+    /// Heat from collisions, only drives the render color.
+    pub temp: F,
+    /// Drag per step, proportional to the squared velocity.
+    pub friction_factor: F,
+    /// Fixed objects never move, but still attract and collide.
+    pub fixed: bool,
+    /// Simulated time left before the world removes the body, infinite unless an emitter set it.
+    pub lifetime: F,
+    /// Stable identifier handed out by `Particles::push`, zero until then.
+    pub id: u64,
+    /// Species or any other label, zero for untagged bodies.
+    pub species: u32,
+    /// Opaque value for the caller, e.g. an index into its own tables.
+    pub user_data: u64,
 }
 
-impl VerletObject {
+impl<F: Float, V: Vector<F>> VerletObject<F, V> {
+    /// Creates a body with a random initial velocity within `initial_velocity_range_bounds`,
+    /// zero bounds give a body at rest.
     pub fn new(
-        x: f64,
-        y: f64,
-        mass: f64,
-        radius: f64,
-        initial_velocity_range_bounds: f64,
-    ) -> VerletObject {
+        position: V,
+        mass: F,
+        radius: F,
+        initial_velocity_range_bounds: F,
+    ) -> VerletObject<F, V> {
         let mut rnd = rand::thread_rng();
+        let mut position_last = position;
+
+        // zero bounds give a cold start, gen_range would panic on the empty range
+        if initial_velocity_range_bounds > F::ZERO {
+            let bounds = -initial_velocity_range_bounds .. initial_velocity_range_bounds;
+            position_last += V::from_vec3(Vec3::new(
+                rnd.gen_range(bounds.clone()),
+                rnd.gen_range(bounds.clone()),
+                if V::DIM == 3 { rnd.gen_range(bounds) } else { F::ZERO },
+            ));
+        }
 
         VerletObject {
-            position: Point::new(x, y),
-            position_last: Point::new(
-                x + rnd.gen_range(-1.0 * initial_velocity_range_bounds .. initial_velocity_range_bounds),
-                y + rnd.gen_range(-1.0 * initial_velocity_range_bounds .. initial_velocity_range_bounds),
-            ),
-            acceleration: Point::new(0.0, 0.0),
-            mass: mass,
-            radius: radius,
-            temp: 0.0,
-            friction_factor: 0.0025,
+            position,
+            position_last,
+            acceleration: V::ZERO,
+            mass,
+            radius,
+            temp: F::ZERO,
+            friction_factor: F::from_f64(0.0025),
+            fixed: false,
+            lifetime: F::INFINITY,
+            id: 0,
+            species: 0,
+            user_data: 0,
         }
     }
 
-    pub fn accelerate(&mut self, acceleration: Point) {
-        self.acceleration = self.acceleration.plus(acceleration);
+    /// Creates a body moving with the given velocity, where `dt` is the integration step.
+    pub fn with_velocity(position: V, velocity: V, mass: F, radius: F, dt: F) -> VerletObject<F, V> {
+        VerletObject {
+            position,
+            position_last: position - velocity * dt,
+            acceleration: V::ZERO,
+            mass,
+            radius,
+            temp: F::ZERO,
+            friction_factor: F::from_f64(0.0025),
+            fixed: false,
+            lifetime: F::INFINITY,
+            id: 0,
+            species: 0,
+            user_data: 0,
+        }
     }
 
-    pub fn update(&mut self, dt: f64) {
-        let mut velocity = self.position.minus(self.position_last.clone());
-        self.position_last = self.position.clone();
+    /// Velocity over the last integration step of length `dt`.
+    pub fn velocity(&self, dt: F) -> V {
+        return (self.position - self.position_last) / dt;
+    }
 
-        self.position = self
-            .position
-            .plus(velocity.plus(self.acceleration.multiply(dt * dt)));
+    /// Adds to the acceleration for the next integration step.
+    pub fn accelerate(&mut self, acceleration: V) {
+        self.acceleration += acceleration;
+    }
 
-        self.acceleration = Point::new(0.0, 0.0);
+    /// Verlet step of length `dt`, fixed bodies only drop their acceleration.
+    pub fn update(&mut self, dt: F) {
+        if self.fixed {
+            self.position_last = self.position;
+            self.acceleration = V::ZERO;
+            return;
+        }
+
+        let velocity = self.position - self.position_last;
+        self.position_last = self.position;
+        self.position += velocity + self.acceleration * (dt * dt);
+
+        self.acceleration = V::ZERO;
     }
 
+    /// Applies `friction_factor` to the velocity.
     pub fn update_friction(&mut self) {
-        let mut velocity = self.position.minus(self.position_last.clone());
-        let velocity_length = f64::sqrt(velocity.length_square());
-        self.position_last = self.position_last.plus(velocity.multiply(velocity_length * self.friction_factor));
+        let velocity = self.position - self.position_last;
+        self.position_last += velocity * (velocity.length() * self.friction_factor);
     }
 
+    /// Cools the body a little and keeps `temp` finite and within range.
     pub fn temp_fix(&mut self) {
+        let max_temp = F::from_f64(500_000.0);
+
         if !self.temp.is_normal() {
-            self.temp = 500_000.0;
+            self.temp = max_temp;
         }
 
-        self.temp -= self.temp * 0.00005;
+        self.temp -= self.temp * F::from_f64(0.00005);
 
-        if self.temp < 0.0 {
-            self.temp = 0.0;
-        }
-
-        if self.temp > 500_000.0 {
-            self.temp = 500_000.0;
-        }
+        self.temp = self.temp.clamp(F::ZERO, max_temp);
     }
 }
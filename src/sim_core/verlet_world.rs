@@ -1,90 +1,135 @@
 use rand::Rng;
+use rayon::prelude::*;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use super::chunk::Chunk;
-use super::point::Point;
+use super::chunk::{Chunk, ChunkTree};
+use super::chunk_tuner::{ChunkSizing, ChunkTuner};
+use super::emitter::Emitter;
+use super::float::Float;
+use super::metrics::StepMetrics;
+use super::particles::{field_at_point, GravitySources, Particles};
+use super::potential::Potential;
+use super::sink::Sink;
+use super::species::Species;
+use super::tracers::Tracers;
+use super::vec2::Vec2;
+use super::vec3::Vec3;
+use super::vector::Vector;
 use super::verlet_object::VerletObject;
 
-pub struct VerletWorld {
-    pub dt: f64,
-    pub gravity_const: f64,
+/// World of bodies constrained to a circle of `costraint_radius` around the origin,
+/// or a sphere when it runs in `Vec3`.
+pub struct VerletWorld<F: Float = f64, V: Vector<F> = Vec2<F>> {
+    /// Simulated time per `update`.
+    pub dt: F,
+    /// Gravitational constant G.
+    pub gravity_const: F,
+    /// Integration sub steps per `update`, see `sub_dt`.
     pub sub_steps: i32,
+    /// Number of random bodies `fill` spawns.
     pub objects_generate_count: i32,
+    /// Updates so far.
     pub step: i32,
+    /// Grid cell size of the current step, set from `chunk_sizing` at the start of `update`.
     pub chunk_size: i32,
-    pub costraint_radius: f64,
-
-    pub objects: Vec<VerletObject>,
-    pub chunks: Vec<Chunk>,
-
-    pub cur_collision_resolve_duration: f64,
-    pub last_collision_resolve_duration: f64,
-
-    pub fill_allowed: bool,
-    pub max_objects_count: i32,
+    /// How `chunk_size` is picked, see `ChunkSizing`.
+    pub chunk_sizing: ChunkSizing,
+    /// Opening angle of the gravity pass, groups of chunks narrower than this times their distance
+    /// pull as a single point mass at their centre of mass. Zero sums over every pair of bodies.
+    pub opening_angle: F,
+    /// Radius of the circle or sphere that keeps the bodies in.
+    pub costraint_radius: F,
+
+    /// The bodies.
+    pub objects: Particles<F, V>,
+    /// Grid of the bodies, rebuilt by `build_chunks` every sub step and cleared by `remove`.
+    pub chunks: Vec<Chunk<F, V>>,
+    /// Massless particles moved by the bodies' gravity in a pass of their own, once per step,
+    /// untouched by sinks and the constraint.
+    pub tracers: Tracers<F, V>,
+
+    /// Sources spawning bodies at the end of every `update`.
+    pub emitters: Vec<Emitter<F, V>>,
+    /// Regions removing bodies after the gravity pass, before constraints could pull them back.
+    pub sinks: Vec<Sink<F, V>>,
+    /// Rules indexed by each body's `species`, see `add_species`.
+    pub species: Vec<Species<F>>,
+    /// Fixed fields pulling on bodies every sub step and on tracers, on top of self-gravity.
+    pub potentials: Vec<Potential>,
+    /// Angular speed around the z axis of the frame the world runs and renders in, zero is inertial.
+    /// Bodies then feel Coriolis and centrifugal forces, and friction drags them towards corotation.
+    pub frame_rotation: F,
+
+    /// Record of the last `update`.
+    pub metrics: StepMetrics,
 }
 
-impl VerletWorld {
-    pub fn new(objects_count: i32, costraint_radius: f64, max_objects_count: i32) -> VerletWorld {
+impl<F: Float, V: Vector<F>> VerletWorld<F, V> {
+    /// Creates an empty world, `objects_count` only drives `fill`.
+    pub fn new(objects_count: i32, costraint_radius: F) -> VerletWorld<F, V> {
         VerletWorld {
-            dt: 0.01,
-            gravity_const: 6.674,
+            dt: F::from_f64(0.01),
+            gravity_const: F::from_f64(6.674),
             sub_steps: 10,
             objects_generate_count: objects_count,
             chunk_size: 20,
+            chunk_sizing: ChunkSizing::Auto(ChunkTuner::new()),
+            opening_angle: F::from_f64(0.5),
             costraint_radius,
-            objects: Vec::new(),
+            objects: Particles::new(),
             chunks: Vec::new(),
+            tracers: Tracers::new(),
 
             step: 0,
-            cur_collision_resolve_duration: 0.0,
-            last_collision_resolve_duration: 0.0,
 
-            fill_allowed: true,
-            max_objects_count,
+            emitters: Vec::new(),
+            sinks: Vec::new(),
+            species: Vec::new(),
+            potentials: Vec::new(),
+            frame_rotation: F::ZERO,
+
+            metrics: StepMetrics::default(),
         }
     }
 
+    /// Spawns `objects_generate_count` random bodies, 3D worlds spread them within `height_bound` along z as well.
     pub fn fill(
         &mut self,
-        width_bound: f64,
-        height_bound: f64,
-        init_velocity_bound: f64,
-        mass_range: std::ops::Range<f64>,
-        radius_range: std::ops::Range<f64>,
+        width_bound: F,
+        height_bound: F,
+        init_velocity_bound: F,
+        mass_range: std::ops::Range<F>,
+        radius_range: std::ops::Range<F>,
         circled: bool
     ) -> &mut Self {
-        if !self.fill_allowed {
-            return self;
-        }
-
         let mut rnd = rand::thread_rng();
 
         if circled {
             for _step in 1..self.objects_generate_count {
-                let position = (
-                    rnd.gen_range(-1.0 * width_bound .. width_bound) * f64::cos((_step as f64) / 1000.0),
-                    rnd.gen_range(-1.0 * width_bound .. width_bound) * f64::sin((_step as f64) / 1000.0),
+                let position = Vec3::new(
+                    rnd.gen_range(-width_bound .. width_bound) * F::from_f64(f64::cos((_step as f64) / 1000.0)),
+                    rnd.gen_range(-width_bound .. width_bound) * F::from_f64(f64::sin((_step as f64) / 1000.0)),
+                    if V::DIM == 3 { rnd.gen_range(-height_bound .. height_bound) } else { F::ZERO },
                 );
 
                 self.objects.push(VerletObject::new(
-                    position.0,
-                    position.1,
+                    V::from_vec3(position),
                     rnd.gen_range(mass_range.clone()),
                     rnd.gen_range(radius_range.clone()),
-                    f64::abs(100.0 * f64::cos(_step as f64 + 0.001)),
+                    F::from_f64(f64::abs(100.0 * f64::cos(_step as f64 + 0.001))),
                 ));
             }
         } else {
             for _step in 0..self.objects_generate_count {
-                let position = (
-                    rnd.gen_range(-1.0 * width_bound..width_bound),
-                    rnd.gen_range(-1.0 * height_bound..height_bound),
+                let position = Vec3::new(
+                    rnd.gen_range(-width_bound..width_bound),
+                    rnd.gen_range(-height_bound..height_bound),
+                    if V::DIM == 3 { rnd.gen_range(-height_bound..height_bound) } else { F::ZERO },
                 );
 
                 self.objects.push(VerletObject::new(
-                    position.0,
-                    position.1,
+                    V::from_vec3(position),
                     rnd.gen_range(mass_range.clone()),
                     rnd.gen_range(radius_range.clone()),
                     init_velocity_bound,
@@ -95,66 +140,165 @@ impl VerletWorld {
         return self;
     }
 
+    /// Adds a body, `velocity` is in world units per unit of time.
+    pub fn spawn(&mut self, position: V, velocity: V, mass: F, radius: F) -> &mut Self {
+        let dt = self.sub_dt();
+        self.objects.push(VerletObject::with_velocity(position, velocity, mass, radius, dt));
+
+        return self;
+    }
+
+    /// Length of one integration sub step, `dt / sub_steps`.
+    pub fn sub_dt(&self) -> F {
+        return self.dt / F::from_f64(self.sub_steps as f64);
+    }
+
+    /// Advances the world by `dt` in `sub_steps` sub steps of collisions, gravity and integration,
+    /// then moves the tracers and logs `metrics` at debug level. The gravity tree is walked on the
+    /// first sub step only, later ones refit it to where the bodies moved, see `resolve_gravity`.
     pub fn update(&mut self) -> &mut Self {
         let time = Instant::now();
+        let mut metrics = StepMetrics::default();
         self.step += 1;
 
+        self.apply_species_friction();
         self.update_chunk_size();
 
-        for _step in 0..self.sub_steps {
-            let duration = self.resolve_collisions();
-            self.update_objects();
-            self.cur_collision_resolve_duration = (self.cur_collision_resolve_duration + duration) / 2.0;
+        let mut gravity = None;
+        for sub_step in 0..self.sub_steps {
+            let phase = Instant::now();
+            self.build_chunks();
+            metrics.grid += phase.elapsed();
+
+            // tracers take their whole step here, where they and the bodies are at the same time
+            if sub_step == 0 {
+                let phase = Instant::now();
+                self.update_tracers();
+                metrics.tracers = phase.elapsed();
+            }
+
+            let phase = Instant::now();
+            self.resolve_collisions();
+            metrics.collisions += phase.elapsed();
+
+            self.update_objects(&mut metrics, &mut gravity);
         }
 
-        self.resolve_gravity();
+        self.absorb();
+
+        let phase = Instant::now();
         self.apply_constraints();
+        metrics.constraints = phase.elapsed();
 
-        let duration: Duration = time.elapsed();
-        if self.objects.len() >= self.max_objects_count as usize {
-            self.fill_allowed = false;
-        } else {
-            self.fill_allowed = true;
+        self.expire();
+        self.emit();
+
+        // the grid of the step's end, for whoever draws or inspects the world
+        let phase = Instant::now();
+        self.build_chunks();
+        metrics.grid += phase.elapsed();
+
+        metrics.step = self.step;
+        metrics.chunk_size = self.chunk_size;
+        metrics.chunk_count = self.chunks.len();
+        metrics.object_count = self.objects.len();
+        metrics.tracer_count = self.tracers.len();
+        metrics.frame = time.elapsed();
+        self.metrics = metrics;
+
+        log::debug!("{}", self.metrics);
+        return self;
+    }
+
+    /// Adds a tracer, `velocity` is in world units per unit of time.
+    pub fn spawn_tracer(&mut self, position: V, velocity: V) -> &mut Self {
+        let dt = self.step_dt();
+        self.tracers.push(position, velocity, dt);
+
+        return self;
+    }
+
+    /// Registers a species and returns the id to put in bodies' `species`.
+    pub fn add_species(&mut self, species: Species<F>) -> u32 {
+        self.species.push(species);
+        return (self.species.len() - 1) as u32;
+    }
+
+    /// Whether the body at `index` takes part in collisions.
+    pub fn collides(&self, index: usize) -> bool {
+        return self.species.get(self.objects.species[index] as usize).is_none_or(|species| species.collides);
+    }
+
+    /// Mass the body at `index` attracts others with, zero unless its species sources gravity.
+    pub fn source_mass(&self, index: usize) -> F {
+        let sources = self.species.get(self.objects.species[index] as usize).is_none_or(|species| species.sources_gravity);
+        return if sources { self.objects.mass[index] } else { F::ZERO };
+    }
+
+    fn apply_species_friction(&mut self) -> &mut Self {
+        if self.species.iter().all(|species| species.friction.is_none()) {
+            return self;
         }
 
-        self.update_objects();
+        for index in 0..self.objects.len() {
+            if let Some(friction) = self.species.get(self.objects.species[index] as usize).and_then(|species| species.friction) {
+                self.objects.friction_factor[index] = friction;
+            }
+        }
 
-        println!("INFO: step={}, chunk_size={}, chunk_count={}, object_count={}, frame_time={:?}", self.step, self.chunk_size, self.chunks.len(), self.objects.len(), duration);
         return self;
     }
 
+    /// Pulls escaping bodies back inside the constraint circle or sphere.
     pub fn apply_constraints(&mut self) -> &mut Self {
-        let mut contraint_center = Point::new(0.0, 0.0);
+        let contraint_center = V::ZERO;
+        let two = F::from_f64(2.0);
 
-        for object in self.objects.iter_mut() {
-            let mut velocity = contraint_center.minus(object.position);
+        let objects = &mut self.objects;
+
+        for index in 0..objects.len() {
+            let position = objects.position(index);
+            let radius = objects.radius[index];
+            let velocity = contraint_center - position;
             let distance = velocity.length();
 
-            if distance > self.costraint_radius - object.radius {
-                let mut diff = velocity.divide(distance);
-                object.position_last = object.position.clone();
-                object.position = contraint_center.minus(diff.multiply(self.costraint_radius - object.radius));
+            if distance > self.costraint_radius - radius {
+                let diff = velocity / distance;
+                objects.set_position_last(index, position);
+                objects.set_position(index, contraint_center - diff * (self.costraint_radius - radius));
             }
 
             // so, box :^)
-            if object.position.0 > self.costraint_radius * 2.0 {
-                object.position.0 = self.costraint_radius;
-                object.position_last.0 = self.costraint_radius;
+            if objects.position_x[index] > self.costraint_radius * two {
+                objects.position_x[index] = self.costraint_radius;
+                objects.position_last_x[index] = self.costraint_radius;
             }
         
-            if object.position.1 > self.costraint_radius * 2.0 {
-                object.position.1 = self.costraint_radius;
-                object.position_last.1 = self.costraint_radius;
+            if objects.position_y[index] > self.costraint_radius * two {
+                objects.position_y[index] = self.costraint_radius;
+                objects.position_last_y[index] = self.costraint_radius;
             }
         
-            if object.position.0 < self.costraint_radius * -2.0 {
-                object.position.0 = self.costraint_radius * -1.0;
-                object.position_last.0 = self.costraint_radius * -1.0;
+            if objects.position_x[index] < -self.costraint_radius * two {
+                objects.position_x[index] = -self.costraint_radius;
+                objects.position_last_x[index] = -self.costraint_radius;
             }
         
-            if object.position.1 < self.costraint_radius * -2.0 {
-                object.position.1 = self.costraint_radius * -1.0;
-                object.position_last.1 = self.costraint_radius * -1.0;
+            if objects.position_y[index] < -self.costraint_radius * two {
+                objects.position_y[index] = -self.costraint_radius;
+                objects.position_last_y[index] = -self.costraint_radius;
+            }
+
+            if V::DIM == 3 {
+                if objects.position_z[index] > self.costraint_radius * two {
+                    objects.position_z[index] = self.costraint_radius;
+                    objects.position_last_z[index] = self.costraint_radius;
+                }
+
+                if objects.position_z[index] < -self.costraint_radius * two {
+                    objects.position_z[index] = -self.costraint_radius;
+                    objects.position_last_z[index] = -self.costraint_radius;
+                }
             }
         }
 
@@ -162,284 +306,864 @@ impl VerletWorld {
     }
 
     fn update_chunk_size(&mut self) -> &mut Self {
-        if ((self.last_collision_resolve_duration + self.cur_collision_resolve_duration) / 2.0)
-            < self.cur_collision_resolve_duration
-        {
-            self.chunk_size = self.chunk_size + 2;
-        } else {
-            self.chunk_size = self.chunk_size - 2;
+        match &mut self.chunk_sizing {
+            ChunkSizing::Fixed(size) => {
+                self.chunk_size = *size;
+            }
+            ChunkSizing::Auto(tuner) => {
+                let max_radius = self.objects.radius.iter().fold(F::ZERO, |max, radius| max.max(*radius));
+                self.chunk_size = tuner.tune(
+                    self.chunk_size,
+                    max_radius.to_f64(),
+                    self.objects.len(),
+                    self.chunks.len(),
+                    V::DIM,
+                    &self.metrics,
+                );
+            }
         }
 
-        if self.chunk_size < 2 {
-            self.chunk_size = 2;
+        return self;
+    }
+
+    /// Removes the body at `index` and moves the last body into its place.
+    /// The grid would point at moved bodies, so it is cleared until the next `build_chunks`.
+    pub fn remove(&mut self, index: usize) -> VerletObject<F, V> {
+        self.chunks.clear();
+        return self.objects.swap_remove(index);
+    }
+
+    // ages bodies by one step and drops those past their lifetime
+    fn expire(&mut self) -> &mut Self {
+        let step_dt = self.step_dt();
+        let mut index = 0;
+        while index < self.objects.len() {
+            self.objects.lifetime[index] -= step_dt;
+            if self.objects.lifetime[index] <= F::ZERO {
+                // the swapped in body was not aged yet, so stay on this index
+                self.remove(index);
+                continue;
+            }
+
+            index += 1;
+        }
+
+        return self;
+    }
+
+    // each body goes to the first sink containing it
+    fn absorb(&mut self) -> &mut Self {
+        if self.sinks.is_empty() {
+            return self;
         }
 
-        if self.chunk_size > 48 {
-            self.chunk_size = 48;
+        let dt = self.sub_dt();
+        let mut index = 0;
+        while index < self.objects.len() {
+            let objects = &self.objects;
+            if let Some(sink) = self.sinks.iter_mut().find(|sink| sink.contains(objects, index)) {
+                sink.record(objects.id(index), objects.mass[index], objects.velocity(index, dt));
+                self.remove(index);
+                continue;
+            }
+
+            index += 1;
         }
 
-        self.last_collision_resolve_duration = self.cur_collision_resolve_duration;
+        return self;
+    }
+
+    fn emit(&mut self) -> &mut Self {
+        let mut rnd = rand::thread_rng();
+        let sub_dt = self.sub_dt();
+        let step_dt = self.step_dt();
+
+        for emitter in self.emitters.iter_mut() {
+            let count = emitter.due(step_dt, self.objects.len());
+            for _step in 0..count {
+                self.objects.push(emitter.sample(&mut rnd, sub_dt));
+            }
+        }
 
         return self;
     }
 
-    fn resolve_collisions(&mut self) -> f64 {
-        let start = Instant::now();
+    /// Collision pass over every pair of bodies sharing a chunk or in touching chunks, diagonal
+    /// neighbours included, each pair once like `resolve_collisions_bruteforce`. Needs `build_chunks` first.
+    pub fn resolve_collisions(&mut self) -> &mut Self {
+        let collides: Vec<bool> = (0..self.objects.len()).map(|index| self.collides(index)).collect();
+        let lookup: HashMap<(i32, i32, i32), usize> = self.chunks.iter().enumerate()
+            .map(|(index, chunk)| ((chunk.x, chunk.y, chunk.z), index))
+            .collect();
+        let neighbours = forward_neighbours(V::DIM);
 
         for chunk_index in 0..self.chunks.len() {
-            let chunk = self.chunks.get(chunk_index).unwrap();
-            let hashes: [(i32, i32); 5] = [
-                (chunk.x, chunk.y),
-                (chunk.x - 1, chunk.y),
-                (chunk.x + 1, chunk.y),
-                (chunk.x, chunk.y - 1),
-                (chunk.x, chunk.y + 1),
-            ];
-    
-            let mut object_indecies: Vec<i32> = Vec::new();
-    
-            for chunk_hash in hashes {
-                let search_result = self.chunks.iter().find(|ch| ch.x == chunk_hash.0 && ch.y == chunk_hash.1);
-                if !search_result.is_none() {
-                    let chunk = search_result.unwrap();
-                    for i in chunk.indecies.iter() {
-                        if !object_indecies.contains(i) || object_indecies.len() == 0 {
-                            object_indecies.push(i + 0);
-                        }
+            let chunk = &self.chunks[chunk_index];
+
+            for i in 0..chunk.indecies.len() {
+                for j in i + 1..chunk.indecies.len() {
+                    let index1 = chunk.indecies[i];
+                    let index2 = chunk.indecies[j];
+                    if collides[index1] && collides[index2] {
+                        apply_collisions(&mut self.objects, index1, index2);
                     }
                 }
             }
-    
-            for i in 0..object_indecies.len() {
-                for j in i..object_indecies.len() {
-                    if i == j {
-                        continue;
-                    }
-    
-                    let get_result = self.objects.get_many_mut([object_indecies[i] as usize, object_indecies[j] as usize]);
-    
-                    if get_result.is_err() {
-                        continue;
-                    } else {
-                        let [object1, object2] = get_result.unwrap();
-                        apply_collisions(object1, object2);
+
+            for (x, y, z) in neighbours.iter() {
+                let Some(other) = lookup.get(&(chunk.x + x, chunk.y + y, chunk.z + z)) else {
+                    continue;
+                };
+
+                for index1 in chunk.indecies.iter() {
+                    for index2 in self.chunks[*other].indecies.iter() {
+                        if collides[*index1] && collides[*index2] {
+                            apply_collisions(&mut self.objects, *index1, *index2);
+                        }
                     }
                 }
             }
         }
 
-        let duration: Duration = start.elapsed();
-        return duration.as_millis() as f64;
+        return self;
     }
 
-    fn resolve_collisions_bruteforce(&mut self) -> f64 {
+    /// Reference O(N^2) collision pass, returns its duration in milliseconds.
+    pub fn resolve_collisions_bruteforce(&mut self) -> f64 {
         let start = Instant::now();
+        let collides: Vec<bool> = (0..self.objects.len()).map(|index| self.collides(index)).collect();
 
         for i in 0..self.objects.len() {
             for j in i..self.objects.len() {
-                if i == j {
+                if i == j || !collides[i] || !collides[j] {
                     continue;
                 }
 
-                let [object1, object2] = self.objects.get_many_mut([i, j]).unwrap();
-
-                apply_collisions(object1, object2);
+                apply_collisions(&mut self.objects, i, j);
             }
         }
 
         let duration: Duration = start.elapsed();
-        return duration.as_millis() as f64;
+        return duration.as_secs_f64() * 1000.0;
     }
 
-    fn resolve_gravity(&mut self) -> &mut Self {
-        for chunk_index_i in 0 .. self.chunks.len() {
-            for chunk_index_j in chunk_index_i .. self.chunks.len() {
-                if chunk_index_i == chunk_index_j {
-                    continue;
-                }
+    /// Gravity pass adding `G m / d^2` towards every gravity source to every body, `d` no less than
+    /// the larger radius. Bodies in nearby chunks pull one by one, groups of chunks further away than
+    /// `opening_angle` allows pull from their centre of mass. Needs `build_chunks` first.
+    pub fn resolve_gravity(&mut self) -> &mut Self {
+        let plan = self.plan_gravity();
+        return self.apply_gravity(&plan);
+    }
+
+    fn plan_gravity(&self) -> GravityPlan<F> {
+        let source_mass: Vec<F> = (0..self.objects.len()).map(|index| self.source_mass(index)).collect();
+        let tree = ChunkTree::new(&self.chunks, self.chunk_size);
+
+        let groups = self.chunks.par_iter().map(|chunk| {
+            let points = chunk.indecies.iter().map(|index| self.objects.position(*index).to_vec3());
+            let (near, far) = self.walk_tree(&tree, &source_mass, (chunk.x, chunk.y, chunk.z), bounds(points));
 
-                let [chunk1, chunk2] = self.chunks.get_many_mut([chunk_index_i, chunk_index_j]).unwrap();
-                for object1_index in chunk1.indecies.iter() {
-                    let object1 = self.objects.get_mut(*object1_index as usize).unwrap();
+            return GravityGroup { targets: chunk.indecies.clone(), near, far };
+        }).collect();
 
-                    let mut velocity = object1.position.minus(chunk2.mass_center);
-                    let velocity_squared = velocity.length_square();
-                    let force = self.gravity_const * ((object1.mass * chunk2.mass) / velocity_squared);
-                    let acceleration = force / f64::sqrt(velocity_squared);
-                    let object_acc = Point::new(chunk2.mass_center.0, chunk2.mass_center.1).minus(Point::new(object1.position.0, object1.position.1)).multiply(acceleration);
-                    object1.accelerate(object_acc);
+        return GravityPlan { tree, source_mass, groups };
+    }
+
+    // moves the plan's nodes to where its chunks' bodies are now, the way `build_chunks` centres chunks
+    fn refit_gravity(&self, plan: &mut GravityPlan<F>) {
+        let chunks: Vec<(F, Vec3<F>)> = plan.groups.iter().map(|group| {
+            let mut mass = F::ZERO;
+            let mut center = Vec3::ZERO;
+            for index in group.targets.iter() {
+                let position = self.objects.position(*index).to_vec3();
+                let source_mass = plan.source_mass[*index];
+                if mass == F::ZERO {
+                    center = position;
+                }
+                if source_mass > F::ZERO {
+                    center = (center * mass + position * source_mass) / (mass + source_mass);
+                    mass += source_mass;
                 }
             }
 
-            let chunk = self.chunks.get(chunk_index_i).unwrap();
-            for i in 0 .. chunk.indecies.len() {
-                for j in i .. chunk.indecies.len() {
-                    if i == j {
-                        continue;
-                    }
+            return (mass, center);
+        }).collect();
 
-                    let [object1, object2] = self.objects.get_many_mut([i, j]).unwrap();
-
-                    let mut velocity = object1.position.minus(Point::new(object2.position.0, object2.position.1));
-                    let velocity_squared = velocity.length_square();
-                    let force = self.gravity_const * ((object1.mass * object2.mass) / velocity_squared);
-                    let acceleration = force / f64::sqrt(velocity_squared);
-    
-                    let object1_acc = object2.position.minus(Point::new(object1.position.0, object1.position.1)).multiply(acceleration);
-                    let object2_acc = object1.position.minus(Point::new(object2.position.0, object2.position.1)).multiply(acceleration);
-    
-                    object1.accelerate(object1_acc);
-                    object2.accelerate(object2_acc);
-                }
+        plan.tree.refit(&chunks);
+    }
+
+    fn apply_gravity(&mut self, plan: &GravityPlan<F>) -> &mut Self {
+        let world = &*self;
+
+        let fields: Vec<Vec<Vec3<F>>> = plan.groups.par_iter().map(|group| {
+            let sources = world.gather_sources(&plan.tree, &plan.source_mass, &group.near, &group.far);
+            world.objects.field_at(&group.targets, &[sources.tail(0)])
+        }).collect();
+
+        for (group, field) in plan.groups.iter().zip(fields) {
+            for (index, field) in group.targets.iter().zip(field) {
+                self.objects.accelerate(*index, V::from_vec3(field * self.gravity_const));
             }
         }
 
         return self;
     }
 
-    fn resolve_gravity_bruteforce(&mut self) -> &mut Self {
-        for i in 0 .. self.objects.len() as usize {
-            for j in i .. self.objects.len() as usize {
-                if i == j {
-                    continue;
-                }
+    // what pulls on a box of bodies or tracers inside `cell`, the source bodies of the chunks
+    // the tree opens for it and the tree nodes of everything further out
+    fn walk_tree(
+        &self,
+        tree: &ChunkTree<F>,
+        source_mass: &[F],
+        cell: (i32, i32, i32),
+        (min, max): (Vec3<F>, Vec3<F>),
+    ) -> (Vec<usize>, Vec<usize>) {
+        let mut chunks = Vec::new();
+        let mut far = Vec::new();
+        tree.walk(cell, min, max, self.opening_angle, &mut chunks, &mut far);
+
+        let near = chunks.iter()
+            .flat_map(|chunk| self.chunks[*chunk].indecies.iter().copied())
+            .filter(|index| source_mass[*index] > F::ZERO)
+            .collect();
+
+        return (near, far);
+    }
 
-                let [object1, object2] = self.objects.get_many_mut([i, j]).unwrap();
+    // point masses of the bodies and nodes `walk_tree` came up with, at their current positions
+    fn gather_sources(&self, tree: &ChunkTree<F>, source_mass: &[F], near: &[usize], far: &[usize]) -> GravitySources<F> {
+        let mut sources = GravitySources::with_capacity(near.len() + far.len());
+        for index in near.iter() {
+            sources.push_body(&self.objects, *index, source_mass[*index]);
+        }
+        for node in far.iter() {
+            let (mass, center) = tree.point_mass(*node);
+            sources.push(center, mass, F::ZERO);
+        }
 
-                let mut velocity = object1.position.minus(Point::new(object2.position.0, object2.position.1));
-                let velocity_squared = velocity.length_square();
-                let force = self.gravity_const * ((object1.mass * object2.mass) / velocity_squared);
-                let acceleration = force / f64::sqrt(velocity_squared);
+        return sources;
+    }
 
-                let object1_acc = object2.position.minus(Point::new(object1.position.0, object1.position.1)).multiply(acceleration);
-                let object2_acc = object1.position.minus(Point::new(object2.position.0, object2.position.1)).multiply(acceleration);
+    /// Simulated time one update advances, `sub_steps` integrations of `sub_dt`.
+    /// Lifetimes, emitter rates, `time` and the tracers' single step all run on this clock.
+    pub fn step_dt(&self) -> F {
+        return self.dt;
+    }
 
-                object1.accelerate(object1_acc);
-                object2.accelerate(object2_acc);
-            }
+    /// Simulated time at the end of the current step, what rotating potentials turn with.
+    pub fn time(&self) -> F {
+        return self.step_dt() * F::from_f64(self.step as f64);
+    }
+
+    /// Sum of `potentials` at `position`, they are laid out in the inertial frame.
+    pub fn external_acceleration(&self, position: V) -> V {
+        let angle = self.frame_angle().to_f64();
+        let position = position.to_vec3().cast().rotate_z(angle);
+        let time = self.time().to_f64();
+        let gravity_const = self.gravity_const.to_f64();
+
+        let mut acceleration = Vec3::ZERO;
+        for potential in self.potentials.iter() {
+            acceleration += potential.acceleration(position, time, gravity_const);
+        }
+
+        return V::from_vec3(acceleration.rotate_z(-angle).cast());
+    }
+
+    /// Angle the frame has turned by since time zero, when it matched the inertial one.
+    pub fn frame_angle(&self) -> F {
+        return self.frame_rotation * self.time();
+    }
+
+    /// Coriolis and centrifugal acceleration of a body at `position` moving with `velocity`.
+    pub fn frame_acceleration(&self, position: V, velocity: V) -> V {
+        let omega = self.frame_rotation;
+        let position = position.to_vec3();
+        let velocity = velocity.to_vec3();
+        let two = F::from_f64(2.0);
+
+        return V::from_vec3(Vec3::new(
+            two * omega * velocity.y + omega * omega * position.x,
+            omega * omega * position.y - two * omega * velocity.x,
+            F::ZERO,
+        ));
+    }
+
+    /// Adds the frame forces to every body's acceleration, on top of what the other passes put there.
+    /// The Coriolis term takes the velocity centred on the current position, which keeps orbits
+    /// from drifting the way they would on the backward difference `velocity`.
+    pub fn apply_frame_forces(&mut self) -> &mut Self {
+        if self.frame_rotation == F::ZERO {
+            return self;
+        }
+
+        let dt = self.sub_dt();
+        for index in 0..self.objects.len() {
+            let position = self.objects.position(index);
+            let acceleration = V::from_vec3(Vec3::new(self.objects.acceleration_x[index], self.objects.acceleration_y[index], F::ZERO));
+            let velocity = self.centered_velocity(position, self.objects.position_last(index), acceleration, dt);
+            let acceleration = self.frame_acceleration(position, velocity);
+            self.objects.accelerate(index, acceleration);
         }
 
         return self;
     }
 
-    fn update_objects(&mut self) {
-        self.chunks.clear();
+    // velocity between the last and the next position, where the next one already feels the Coriolis
+    // pull of this velocity: (I - c J) u = w + b + c J w for the step u, with w the last step,
+    // b the other accelerations and the centrifugal one times dt^2, c = omega dt and J (x, y) = (y, -x)
+    fn centered_velocity(&self, position: V, position_last: V, acceleration: V, dt: F) -> V {
+        let omega = self.frame_rotation;
+        let c = omega * dt;
+        let last_step = (position - position_last).to_vec3();
+        let push = (acceleration.to_vec3() + position.to_vec3() * (omega * omega)) * (dt * dt);
+
+        let rhs_x = last_step.x + push.x + c * last_step.y;
+        let rhs_y = last_step.y + push.y - c * last_step.x;
+        let determinant = F::ONE + c * c;
+        let step_x = (rhs_x + c * rhs_y) / determinant;
+        let step_y = (rhs_y - c * rhs_x) / determinant;
+
+        let two_dt = F::from_f64(2.0) * dt;
+        return V::from_vec3(Vec3::new((step_x + last_step.x) / two_dt, (step_y + last_step.y) / two_dt, last_step.z / dt));
+    }
+
+    /// Position in the inertial frame of a point given in the world's frame.
+    pub fn to_inertial_position(&self, position: V) -> V {
+        return V::from_vec3(position.to_vec3().rotate_z(self.frame_angle()));
+    }
+
+    /// Velocity in the inertial frame of a point at `position` moving with `velocity` in the world's frame.
+    pub fn to_inertial_velocity(&self, position: V, velocity: V) -> V {
+        let position = position.to_vec3();
+        let spin = Vec3::new(-position.y, position.x, F::ZERO) * self.frame_rotation;
+        return V::from_vec3((velocity.to_vec3() + spin).rotate_z(self.frame_angle()));
+    }
 
-        for object in self.objects.iter_mut() {
-            // hot fix irrational acceleration
-            if !object.acceleration.0.is_normal() {
-                object.acceleration.0 = 0.0;
+    /// Position in the world's frame of a point given in the inertial frame.
+    pub fn from_inertial_position(&self, position: V) -> V {
+        return V::from_vec3(position.to_vec3().rotate_z(-self.frame_angle()));
+    }
+
+    /// Velocity in the world's frame of a point at inertial `position` moving with inertial `velocity`,
+    /// e.g. to spawn bodies set up for an inertial world.
+    pub fn from_inertial_velocity(&self, position: V, velocity: V) -> V {
+        let position = position.to_vec3();
+        let spin = Vec3::new(-position.y, position.x, F::ZERO) * self.frame_rotation;
+        return V::from_vec3((velocity.to_vec3() - spin).rotate_z(-self.frame_angle()));
+    }
+
+    /// Copy of the body at `index` in the inertial frame, for exporting runs of a rotating world.
+    pub fn inertial_object(&self, index: usize) -> VerletObject<F, V> {
+        let dt = self.sub_dt();
+        let mut object = self.objects.get(index);
+
+        // the last position is turned by the frame's angle one sub step ago, so the velocity implied
+        // by the pair is the inertial one at the same half step the world's pair describes
+        object.position = self.to_inertial_position(object.position);
+        object.position_last = V::from_vec3(object.position_last.to_vec3().rotate_z(self.frame_angle() - self.frame_rotation * dt));
+        object.acceleration = V::from_vec3(object.acceleration.to_vec3().rotate_z(self.frame_angle()));
+
+        return object;
+    }
+
+    /// Adds the `potentials` to every body's acceleration.
+    pub fn apply_potentials(&mut self) -> &mut Self {
+        if self.potentials.is_empty() {
+            return self;
+        }
+
+        for index in 0..self.objects.len() {
+            let acceleration = self.external_acceleration(self.objects.position(index));
+            self.objects.accelerate(index, acceleration);
+        }
+
+        return self;
+    }
+
+    fn update_tracers(&mut self) -> &mut Self {
+        if self.tracers.is_empty() {
+            return self;
+        }
+
+        let dt = self.step_dt();
+        self.resolve_tracer_gravity();
+        if self.frame_rotation != F::ZERO {
+            for index in 0..self.tracers.len() {
+                let position = self.tracers.position[index];
+                let velocity = self.centered_velocity(position, self.tracers.position_last[index], self.tracers.acceleration[index], dt);
+                let acceleration = self.frame_acceleration(position, velocity);
+                self.tracers.acceleration[index] += acceleration;
             }
+        }
+        self.tracers.integrate(dt);
+
+        return self;
+    }
+
+    /// Gravity on the tracers, what `resolve_gravity` gives a body of zero radius in their place.
+    /// Tracers sharing a grid cell share one walk of the tree, needs `build_chunks` first.
+    pub fn resolve_tracer_gravity(&mut self) -> &mut Self {
+        if self.tracers.is_empty() {
+            return self;
+        }
+
+        let source_mass: Vec<F> = (0..self.objects.len()).map(|index| self.source_mass(index)).collect();
+        let tree = ChunkTree::new(&self.chunks, self.chunk_size);
+        let world = &*self;
+
+        let mut cells: HashMap<(i32, i32, i32), Vec<usize>> = HashMap::new();
+        for (index, position) in self.tracers.position.iter().enumerate() {
+            cells.entry(position_to_chunk_coord(*position, self.chunk_size)).or_default().push(index);
+        }
+        let cells: Vec<((i32, i32, i32), Vec<usize>)> = cells.into_iter().collect();
+
+        let accelerations: Vec<Vec<V>> = cells.par_iter().map(|(cell, indices)| {
+            let points = indices.iter().map(|index| world.tracers.position[*index].to_vec3());
+            let (near, far) = world.walk_tree(&tree, &source_mass, *cell, bounds(points));
+            let sources = world.gather_sources(&tree, &source_mass, &near, &far);
+
+            return indices.iter().map(|index| {
+                let position = world.tracers.position[*index];
+                let field = field_at_point(position.to_vec3(), F::ZERO, V::DIM == 3, &[sources.tail(0)]);
 
-            if !object.acceleration.1.is_normal() {
-                object.acceleration.1 = 0.0;
+                V::from_vec3(field * world.gravity_const) + world.external_acceleration(position)
+            }).collect();
+        }).collect();
+
+        for ((_cell, indices), accelerations) in cells.iter().zip(accelerations) {
+            for (index, acceleration) in indices.iter().zip(accelerations) {
+                self.tracers.acceleration[*index] += acceleration;
             }
+        }
+
+        return self;
+    }
 
-            object.update(self.dt / self.sub_steps as f64);
-            object.update_friction();
-            object.temp_fix();
+    /// Reference O(N^2) gravity pass.
+    pub fn resolve_gravity_bruteforce(&mut self) -> &mut Self {
+        if self.species.iter().all(|species| species.sources_gravity) {
+            self.objects.accumulate_gravity(self.gravity_const, None);
+            return self;
         }
 
+        let source_mass: Vec<F> = (0..self.objects.len()).map(|index| self.source_mass(index)).collect();
+        self.objects.accumulate_gravity(self.gravity_const, Some(&source_mass));
+
+        return self;
+    }
+
+    fn update_objects(&mut self, metrics: &mut StepMetrics, gravity: &mut Option<GravityPlan<F>>) {
+        let phase = Instant::now();
+        // the first sub step walks the tree, the later ones refit it to the moved bodies
+        if let Some(plan) = gravity.as_mut() {
+            self.refit_gravity(plan);
+        }
+        let plan = gravity.get_or_insert_with(|| self.plan_gravity());
+        self.apply_gravity(plan);
+        self.apply_potentials();
+        metrics.gravity += phase.elapsed();
+
+        let phase = Instant::now();
+        let dt = self.sub_dt();
+        self.apply_frame_forces();
+        self.objects.integrate(dt);
+        metrics.integration += phase.elapsed();
+    }
+
+    /// Sorts all bodies into chunks of `chunk_size`, chunk masses only count gravity sources.
+    pub fn build_chunks(&mut self) -> &mut Self {
+        self.chunks.clear();
         for object_index in 0..self.objects.len() {
-            self.push_to_chunks(object_index);
+            let mass = self.source_mass(object_index);
+            self.push_to_chunks(object_index, mass);
         }
+
+        return self;
     }
 
-    fn push_to_chunks(&mut self, object_index: usize) -> &mut Self {
-        let object: &mut VerletObject = self.objects.get_mut(object_index).unwrap();
-        let (chunk_x, chunk_y) = position_to_chunk_coord(object, self.chunk_size);
-        let chunk_position_in_vec = self.chunks.iter().position(|ch| ch.x == chunk_x && ch.y == chunk_y);
-        if chunk_position_in_vec.is_none() {
+    fn push_to_chunks(&mut self, object_index: usize, mass: F) -> &mut Self {
+        let position = self.objects.position(object_index);
+        let (chunk_x, chunk_y, chunk_z) = position_to_chunk_coord(position, self.chunk_size);
+        let chunk_position_in_vec = self.chunks.iter().position(|ch| ch.x == chunk_x && ch.y == chunk_y && ch.z == chunk_z);
+        if let Some(chunk_pos) = chunk_position_in_vec {
+            // andrew mutate :^)
+            let chunk = self.chunks.get_mut(chunk_pos).unwrap();
+            chunk.indecies.push(object_index);
+            // bodies without source mass leave the centre alone
+            if chunk.mass == F::ZERO {
+                chunk.mass_center = position;
+            }
+            if mass > F::ZERO {
+                chunk.mass_center = (chunk.mass_center * chunk.mass + position * mass) / (chunk.mass + mass);
+                chunk.mass += mass;
+            }
+        } else {
             // create
-            let mut indecies: Vec<i32> = Vec::new();
-            indecies.push(object_index as i32);
-
             self.chunks.push(Chunk {
                 x: chunk_x,
                 y: chunk_y,
-                indecies: indecies,
-                mass_center: object.position.clone(),
-                mass: object.mass
+                z: chunk_z,
+                indecies: vec![object_index],
+                mass_center: position,
+                mass
             });
-        } else {
-            // andrew mutate :^)
-            let chunk_pos = chunk_position_in_vec.unwrap();
-            let chunk = self.chunks.get_mut(chunk_pos).unwrap();
-            chunk.indecies.push(object_index as i32);
-            chunk.mass += object.mass;
-            chunk.mass_center.0 = (chunk.mass_center.0 + object.position.0) / 2.0;
-            chunk.mass_center.1 = (chunk.mass_center.1 + object.position.1) / 2.0;
         }
 
         return self;
     }
 }
 
-fn apply_collisions(object1: &mut VerletObject, object2: &mut VerletObject) -> bool {
-    let collide_responsibility = 0.375;
-    let mut velocity = object1
-        .position
-        .minus(Point::new(object2.position.0, object2.position.1));
+// who pulls on whom, planned on the grid of an update's first sub step and refitted on the
+// later ones, bodies keep their chunk's sources while they move within the update
+struct GravityPlan<F: Float> {
+    tree: ChunkTree<F>,
+    source_mass: Vec<F>,
+    groups: Vec<GravityGroup>,
+}
+
+// bodies of one chunk, the source bodies pulling on them one by one and the tree nodes pulling as points
+struct GravityGroup {
+    targets: Vec<usize>,
+    near: Vec<usize>,
+    far: Vec<usize>,
+}
+
+fn apply_collisions<F: Float, V: Vector<F>>(objects: &mut Particles<F, V>, index1: usize, index2: usize) -> bool {
+    let collide_responsibility = F::from_f64(0.375);
+    let two = F::from_f64(2.0);
+    let velocity = objects.position(index1) - objects.position(index2);
     let distance_squared = velocity.length_square();
-    let distance_minimal = object1.radius + object2.radius;
+    let distance_minimal = objects.radius[index1] + objects.radius[index2];
 
     // no overlap, skip
     if distance_squared >= (distance_minimal * distance_minimal) {
         return false;
     }
 
-    let distance = f64::sqrt(distance_squared);
-    let mut diff = velocity.divide(distance);
+    let distance = distance_squared.sqrt();
+    let diff = velocity / distance;
+
+    let object1_mass = objects.mass[index1];
+    let object2_mass = objects.mass[index2];
+    let common_mass = object1_mass + object2_mass;
 
-    let common_mass = object1.mass + object2.mass;
-    let object1_mass_ratio = object1.mass / common_mass;
-    let object2_mass_ratio = object2.mass / common_mass;
+    // a fixed object acts as infinitely heavy, the other one takes the whole push
+    let (object1_mass_ratio, object2_mass_ratio) = match (objects.fixed[index1], objects.fixed[index2]) {
+        (true, true) => (F::ZERO, F::ZERO),
+        (true, false) => (F::ONE, F::ZERO),
+        (false, true) => (F::ZERO, F::ONE),
+        (false, false) => (object1_mass / common_mass, object2_mass / common_mass),
+    };
 
     let delta = collide_responsibility * (distance - distance_minimal);
 
-    object1.position = object1
-        .position
-        .minus(diff.multiply(object2_mass_ratio * delta).divide(2.0));
-    object2.position = object2
-        .position
-        .plus(diff.multiply(object1_mass_ratio * delta).divide(2.0));
+    let mut position1 = objects.position(index1) - diff * (object2_mass_ratio * delta) / two;
+    let mut position2 = objects.position(index2) + diff * (object1_mass_ratio * delta) / two;
 
     // hot fix for irrational value
-    if !object1.position.0.is_normal() || !object1.position.1.is_normal() {
-        object1.position = Point::new(0.0, 0.0);
-        object1.position_last = object1.position.clone();
+    if !is_sane(position1) {
+        position1 = V::ZERO;
+        objects.set_position_last(index1, position1);
     }
 
-    if !object2.position.0.is_normal() || !object2.position.1.is_normal() {
-        object2.position = Point::new(0.0, 0.0);
-        object2.position_last = object2.position.clone();
+    if !is_sane(position2) {
+        position2 = V::ZERO;
+        objects.set_position_last(index2, position2);
     }
 
+    objects.set_position(index1, position1);
+    objects.set_position(index2, position2);
+
     // implementation of temperature
-    let object1_speed = object1
-        .position
-        .minus(Point::new(object1.position_last.0, object1.position_last.1))
-        .length_square();
-    let object2_speed = object2
-        .position
-        .minus(Point::new(object2.position_last.0, object2.position_last.1))
-        .length_square();
+    let object1_speed = (position1 - objects.position_last(index1)).length_square();
+    let object2_speed = (position2 - objects.position_last(index2)).length_square();
+
+    let heating = F::from_f64(25.0);
+    let conduction = F::from_f64(0.075);
 
-    object1.temp += common_mass * object2_speed * object2_speed * 25.0;
-    object2.temp += common_mass * object1_speed * object1_speed * 25.0;
+    let mut temp1 = objects.temp[index1] + common_mass * object2_speed * object2_speed * heating;
+    let mut temp2 = objects.temp[index2] + common_mass * object1_speed * object1_speed * heating;
 
-    let temp_to_obj1 = object2.temp * (object2_mass_ratio * 0.075);
-    let temp_to_obj2 = object1.temp * (object1_mass_ratio * 0.075);
+    let temp_to_obj1 = temp2 * (object2_mass_ratio * conduction);
+    let temp_to_obj2 = temp1 * (object1_mass_ratio * conduction);
 
-    object1.temp = object1.temp + temp_to_obj1 - temp_to_obj2;
-    object2.temp = object2.temp + temp_to_obj2 - temp_to_obj1;
+    temp1 = temp1 + temp_to_obj1 - temp_to_obj2;
+    temp2 = temp2 + temp_to_obj2 - temp_to_obj1;
+
+    objects.temp[index1] = temp1;
+    objects.temp[index2] = temp2;
 
     return true;
 }
 
-fn position_to_chunk_coord(object: &mut VerletObject, chunk_size: i32) -> (i32, i32) {
+// box around `points`, `z` stays zero in 2D
+fn bounds<F: Float>(points: impl Iterator<Item = Vec3<F>>) -> (Vec3<F>, Vec3<F>) {
+    let mut min = Vec3::new(F::INFINITY, F::INFINITY, F::INFINITY);
+    let mut max = -min;
+    for position in points {
+        min = Vec3::new(min.x.min(position.x), min.y.min(position.y), min.z.min(position.z));
+        max = Vec3::new(max.x.max(position.x), max.y.max(position.y), max.z.max(position.z));
+    }
+
+    return (min, max);
+}
+
+// neighbour offsets after (0, 0, 0) in z, y, x order, the other half sees this chunk as its neighbour
+// so every pair of touching chunks comes up once, 4 of 8 in 2D and 13 of 26 in 3D
+fn forward_neighbours(dim: usize) -> Vec<(i32, i32, i32)> {
+    let layers = if dim == 3 { -1..=1 } else { 0..=0 };
+    let mut offsets = Vec::new();
+    for z in layers {
+        for y in -1..=1 {
+            for x in -1..=1 {
+                if (z, y, x) > (0, 0, 0) {
+                    offsets.push((x, y, z));
+                }
+            }
+        }
+    }
+
+    return offsets;
+}
+
+// z is only checked for being finite, planar setups in 3D keep it at exactly zero
+fn is_sane<F: Float, V: Vector<F>>(position: V) -> bool {
+    let position = position.to_vec3();
+    return position.x.is_normal() && position.y.is_normal() && position.z.is_finite();
+}
+
+fn position_to_chunk_coord<F: Float, V: Vector<F>>(position: V, chunk_size: i32) -> (i32, i32, i32) {
+    let position = position.to_vec3();
+    let size = F::from_f64(f64::from(chunk_size));
+
     return (
-        f64::floor(object.position.0 / f64::from(chunk_size)) as i32, 
-        f64::floor(object.position.1 / f64::from(chunk_size)) as i32
+        (position.x / size).floor().to_f64() as i32,
+        (position.y / size).floor().to_f64() as i32,
+        (position.z / size).floor().to_f64() as i32,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    // two overlapping bodies either side of a cell corner, so their cells only touch diagonally
+    fn corner_pair<V: Vector<f64>>(offset: V) -> VerletWorld<f64, V> {
+        let mut world = VerletWorld::<f64, V>::new(0, 540.0);
+        world.chunk_size = 20;
+        world.spawn(V::from_vec3(Vec3::new(20.0, 20.0, 20.0)) + offset * 0.5, V::ZERO, 1.0, 1.0);
+        world.spawn(V::from_vec3(Vec3::new(20.0, 20.0, 20.0)) - offset * 0.5, V::ZERO, 1.0, 1.0);
+        world.build_chunks();
+
+        return world;
+    }
+
+    #[test]
+    fn remove_leaves_no_stale_chunks() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        world.spawn(Vec2::new(0.0, 0.0), Vec2::ZERO, 1.0, 1.0);
+        world.spawn(Vec2::new(50.0, 0.0), Vec2::ZERO, 1.0, 1.0);
+        world.spawn(Vec2::new(100.0, 0.0), Vec2::ZERO, 1.0, 1.0);
+        world.build_chunks();
+
+        world.remove(0);
+        world.resolve_gravity();
+        world.resolve_collisions();
+        world.resolve_tracer_gravity();
+        assert!(world.chunks.is_empty());
+
+        world.build_chunks();
+        let indices: usize = world.chunks.iter().map(|chunk| chunk.indecies.len()).sum();
+        assert_eq!(indices, 2);
+    }
+
+    #[test]
+    fn lifetimes_and_rates_follow_world_time() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        let mut emitter = Emitter::new(Vec2::ZERO, 100.0);
+        emitter.lifetime_range = Some(0.5..0.5);
+        world.emitters.push(emitter);
+
+        let mut emitted = 0;
+        while world.time() < 0.5 - 1e-9 {
+            world.update();
+            emitted = world.emitters[0].emitted;
+        }
+        // every body spawned so far is younger than its lifetime
+        assert_eq!(emitted, (100.0 * world.time() + 1e-9).floor() as usize);
+        assert_eq!(world.objects.len(), emitted);
+
+        world.update();
+        assert!(world.objects.len() < world.emitters[0].emitted);
+    }
+
+    #[test]
+    fn one_chunk_gravity_matches_bruteforce() {
+        let mut chunked = VerletWorld::<f64, Vec3>::new(0, 540.0);
+        chunked.chunk_size = 1000;
+        for index in 0..50 {
+            let angle = index as f64;
+            let position = Vec3::new(angle.cos(), angle.sin(), (angle * 0.3).sin()) * (10.0 + angle) + Vec3::new(100.0, 100.0, 100.0);
+            chunked.spawn(position, Vec3::ZERO, 1.0 + angle, 0.5);
+        }
+        let mut bruteforce = VerletWorld::<f64, Vec3>::new(0, 540.0);
+        bruteforce.objects = chunked.objects.clone();
+
+        chunked.build_chunks();
+        assert_eq!(chunked.chunks.len(), 1);
+        chunked.resolve_gravity();
+        bruteforce.resolve_gravity_bruteforce();
+
+        for index in 0..50 {
+            let expected = bruteforce.objects.get(index).acceleration;
+            let actual = chunked.objects.get(index).acceleration;
+            assert!((actual - expected).length() <= 1e-12 * expected.length());
+        }
+    }
+
+    #[test]
+    fn refitted_gravity_matches_fresh_pass() {
+        let mut refitted = still_cloud::<f64>();
+        refitted.chunk_size = 20;
+        refitted.build_chunks();
+        let mut plan = refitted.plan_gravity();
+
+        // small enough that every body stays in its cell, so a fresh walk groups them the same
+        for index in 0..refitted.objects.len() {
+            let offset = Vec2::new((index as f64).sin(), (index as f64).cos()) * 1e-3;
+            refitted.objects.set_position(index, refitted.objects.position(index) + offset);
+        }
+        let mut fresh = VerletWorld::<f64, Vec2>::new(0, 100.0);
+        fresh.objects = refitted.objects.clone();
+        fresh.chunk_size = 20;
+        fresh.build_chunks();
+        assert_eq!(fresh.chunks.len(), plan.groups.len());
+
+        refitted.refit_gravity(&mut plan);
+        refitted.apply_gravity(&plan);
+        fresh.resolve_gravity();
+
+        for index in 0..refitted.objects.len() {
+            let expected = fresh.objects.get(index).acceleration;
+            let actual = refitted.objects.get(index).acceleration;
+            assert!((actual - expected).length() <= 1e-12 * expected.length());
+        }
+    }
+
+    #[test]
+    fn tracer_keeps_up_with_light_body() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        let speed = f64::sqrt(world.gravity_const * 1000.0 / 20.0);
+        world.spawn(Vec2::ZERO, Vec2::ZERO, 1000.0, 1.0);
+        world.spawn(Vec2::new(20.0, 0.0), Vec2::new(0.0, speed), 1.0e-6, 0.1);
+        world.spawn_tracer(Vec2::new(20.0, 0.0), Vec2::new(0.0, speed));
+        world.objects.friction_factor.fill(0.0);
+
+        let period = 2.0 * std::f64::consts::PI * 20.0 / speed;
+        while world.time() < period {
+            world.update();
+        }
+
+        let body = world.objects.position(1);
+        assert!(((body - world.objects.position(0)).length() - 20.0).abs() < 0.02);
+        assert!((world.tracers.position[0] - body).length() < 0.01);
+    }
+
+    // seeded cloud of equal masses without friction, so its centre of mass must stay put
+    fn still_cloud<F: Float>() -> VerletWorld<F> {
+        let mut rnd = StdRng::seed_from_u64(37);
+        let mut world = VerletWorld::new(0, F::from_f64(100.0));
+        for _step in 0..200 {
+            let position = Vec2::new(rnd.gen_range(-100.0..100.0), rnd.gen_range(-100.0..100.0));
+            world.spawn(position.cast(), Vec2::ZERO, F::from_f64(20.0), F::ONE);
+        }
+        world.objects.friction_factor.fill(F::ZERO);
+
+        return world;
+    }
+
+    fn center_of_mass<F: Float>(world: &VerletWorld<F>) -> Vec2 {
+        let sum = world.objects.iter().fold(Vec2::ZERO, |sum, object| sum + object.position.cast());
+        return sum / world.objects.len() as f64;
+    }
+
+    #[test]
+    fn f32_drifts_more_than_f64_but_stays_bounded() {
+        let mut single = still_cloud::<f32>();
+        let mut double = still_cloud::<f64>();
+        let start_single = center_of_mass(&single);
+        let start_double = center_of_mass(&double);
+
+        // gravity only, collisions would make the runs part for reasons other than precision
+        for _step in 0..1000 {
+            single.resolve_gravity_bruteforce();
+            single.objects.integrate(single.sub_dt());
+            double.resolve_gravity_bruteforce();
+            double.objects.integrate(double.sub_dt());
+        }
+
+        let drift_single = (center_of_mass(&single) - start_single).length();
+        let drift_double = (center_of_mass(&double) - start_double).length();
+        // about 0.06 and 4e-12 across a cloud 200 wide
+        assert!(drift_single < 0.5, "f32 drift {drift_single}");
+        assert!(drift_double < 1e-9, "f64 drift {drift_double}");
+        assert!(drift_single > drift_double);
+    }
+
+    #[test]
+    fn frame_conversions_round_trip() {
+        let mut world = VerletWorld::<f64, Vec3>::new(0, 540.0);
+        world.frame_rotation = 0.3;
+        world.step = 7;
+
+        let position = Vec3::new(30.0, -40.0, 5.0);
+        let velocity = Vec3::new(2.0, 1.0, -0.5);
+        let inertial_position = world.to_inertial_position(position);
+        let inertial_velocity = world.to_inertial_velocity(position, velocity);
+        assert!((inertial_position - position).length() > 1.0);
+
+        assert!((world.from_inertial_position(inertial_position) - position).length() < 1e-12);
+        assert!((world.from_inertial_velocity(inertial_position, inertial_velocity) - velocity).length() < 1e-12);
+    }
+
+    #[test]
+    fn body_at_rest_circles_in_rotating_frame() {
+        let mut world = VerletWorld::<f64, Vec2>::new(0, 540.0);
+        world.frame_rotation = 0.5;
+        let start = Vec2::new(100.0, 0.0);
+        world.spawn(start, Vec2::ZERO, 1.0, 1.0);
+        world.objects.friction_factor.fill(0.0);
+        // one sub step back on the exact path, the frame had not turned as far yet
+        let angle = world.frame_rotation * world.sub_dt();
+        world.objects.set_position_last(0, Vec2::new(100.0 * angle.cos(), 100.0 * angle.sin()));
+
+        let period = 2.0 * std::f64::consts::PI / world.frame_rotation;
+        while world.time() < period {
+            world.update();
+
+            // turning backwards at the frame's rate, standing still once turned back
+            let position = world.objects.position(0);
+            assert!((position - world.from_inertial_position(start)).length() < 1e-3, "{position:?} at {}", world.time());
+            let inertial = world.inertial_object(0);
+            assert!((inertial.position - start).length() < 1e-3);
+            assert!(inertial.velocity(world.sub_dt()).length() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn collisions_reach_diagonal_neighbours() {
+        let mut world = corner_pair(Vec2::new(0.5, 0.5));
+        assert_eq!(world.chunks.len(), 2);
+        world.resolve_collisions();
+        assert!((world.objects.position(0) - world.objects.position(1)).length() > 0.5_f64.hypot(0.5));
+
+        let mut world = corner_pair(Vec3::new(0.5, 0.5, 0.5));
+        assert_eq!(world.chunks.len(), 2);
+        world.resolve_collisions();
+        assert!((world.objects.position(0) - world.objects.position(1)).length() > Vec3::new(0.5, 0.5, 0.5).length());
+    }
+}